@@ -0,0 +1,52 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use http_types::range::ByteRanges;
+
+fn parse(c: &mut Criterion) {
+    c.bench_function("parse single range", |b| {
+        b.iter(|| black_box("bytes=0-499").parse::<ByteRanges>().unwrap())
+    });
+
+    let multi = "bytes=0-99,200-299,400-499,600-699,800-899,1000-1099,\
+                 1200-1299,1400-1499,1600-1699,-500";
+    c.bench_function("parse 10 ranges", |b| {
+        b.iter(|| black_box(multi).parse::<ByteRanges>().unwrap())
+    });
+}
+
+fn coalesce(c: &mut Criterion) {
+    let ranges: ByteRanges = "bytes=0-99,50-149,300-399,150-199,-500,1000-"
+        .parse()
+        .unwrap();
+    c.bench_function("coalesce", |b| {
+        b.iter(|| {
+            let mut ranges = ranges.clone();
+            ranges.coalesce(black_box(10_000));
+            ranges
+        })
+    });
+}
+
+fn coalesce_overlapping(c: &mut Criterion) {
+    let mut ranges = ByteRanges::new();
+    for i in (0..1_000).rev() {
+        ranges.push(i * 10, i * 10 + 10);
+    }
+    c.bench_function("coalesce 1000 overlapping ranges", |b| {
+        b.iter(|| {
+            let mut ranges = ranges.clone();
+            ranges.coalesce(black_box(100_000));
+            ranges
+        })
+    });
+}
+
+fn serialize(c: &mut Criterion) {
+    let ranges: ByteRanges = "bytes=0-99,200-299,400-499,600-699,800-899,1000-1099,\
+                              1200-1299,1400-1499,1600-1699,-500"
+        .parse()
+        .unwrap();
+    c.bench_function("value", |b| b.iter(|| black_box(&ranges).value()));
+}
+
+criterion_group!(benches, parse, coalesce, coalesce_overlapping, serialize);
+criterion_main!(benches);
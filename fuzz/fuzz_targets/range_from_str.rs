@@ -0,0 +1,13 @@
+#![no_main]
+use http_types::range::{AcceptRanges, ByteContentRange, ByteRange, ByteRanges};
+use libfuzzer_sys::fuzz_target;
+
+// No input to any of the range parsers should ever panic.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        let _ = s.parse::<ByteRange>();
+        let _ = s.parse::<ByteRanges>();
+        let _ = s.parse::<ByteContentRange>();
+        let _ = s.parse::<AcceptRanges>();
+    }
+});
@@ -0,0 +1,28 @@
+#![no_main]
+use http_types::range::{ByteRange, ByteRanges};
+use libfuzzer_sys::fuzz_target;
+
+// Resolving arbitrary ranges against an arbitrary size should never panic, and
+// valid ranges should always parse back from their serialized form.
+fuzz_target!(|input: (ByteRanges, ByteRange, u64)| {
+    let (ranges, range, size) = input;
+
+    let _ = range.resolve(size);
+    let _ = range.split(size, 4);
+    let _ = ranges.resolve(size);
+
+    // Derive the chunk length from the size so each range yields a bounded
+    // number of chunks.
+    let _ = ranges.chunked(size, size / 64 + 1);
+
+    if let Ok(range) = ByteRange::try_new(range.start, range.end) {
+        assert_eq!(range.to_string().parse::<ByteRange>().unwrap(), range);
+    }
+
+    let valid = ranges
+        .iter()
+        .all(|range| ByteRange::try_new(range.start, range.end).is_ok());
+    if valid && !ranges.is_empty() {
+        assert_eq!(ranges.to_string().parse::<ByteRanges>().unwrap(), ranges);
+    }
+});
@@ -18,6 +18,11 @@ pub(crate) fn parse_token(input: &str) -> (Option<&str>, &str) {
     }
 }
 
+/// https://tools.ietf.org/html/rfc7230#section-3.2.6
+pub(crate) fn is_token(input: &str) -> bool {
+    !input.is_empty() && input.chars().all(tchar)
+}
+
 /// https://tools.ietf.org/html/rfc7230#section-3.2.6
 fn tchar(c: char) -> bool {
     matches!(
@@ -0,0 +1,624 @@
+use crate::headers::{HeaderName, HeaderValue, Headers, ToHeaderValues, ACCEPT_RANGES};
+use crate::parse_utils::is_token;
+use crate::range::{RangeError, Unit};
+use crate::StatusCode;
+
+use std::fmt::{self, Display};
+use std::option;
+use std::str::FromStr;
+
+/// Advertise the range units supported by the server.
+///
+/// An instance without any unit advertises `none`: adding a unit replaces
+/// `none`, and removing the last unit brings it back.
+///
+/// # Specifications
+///
+/// - [RFC 7233, section 2.3: Accept-Ranges](https://tools.ietf.org/html/rfc7233#section-2.3)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Response;
+/// use http_types::range::AcceptRanges;
+///
+/// let accept_ranges = AcceptRanges::with_bytes();
+///
+/// let mut res = Response::new(200);
+/// accept_ranges.apply(&mut res);
+///
+/// let accept_ranges = AcceptRanges::from_headers(res)?.unwrap();
+/// assert!(accept_ranges.supports_bytes());
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct AcceptRanges {
+    units: Vec<Unit>,
+}
+
+impl AcceptRanges {
+    /// Create a new instance which does not accept any range requests.
+    pub fn new() -> Self {
+        Self { units: Vec::new() }
+    }
+
+    /// Create a new instance accepting the `bytes` range unit.
+    pub fn with_bytes() -> Self {
+        Self {
+            units: vec![Unit::Bytes],
+        }
+    }
+
+    /// Create a new instance accepting a custom range unit.
+    ///
+    /// The unit is read the same way the parser reads each entry of the
+    /// header: an empty name, `none`, or a name which is not a valid token
+    /// results in an instance which does not accept any range requests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::AcceptRanges;
+    ///
+    /// assert_eq!(AcceptRanges::with_other("pages").to_string(), "pages");
+    /// assert!(AcceptRanges::with_other("").is_none());
+    /// assert!(AcceptRanges::with_other("a, b").is_none());
+    /// ```
+    pub fn with_other(unit: &str) -> Self {
+        Self {
+            units: parse_unit(unit).into_iter().collect(),
+        }
+    }
+
+    /// Add a range unit, returning the updated instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::{AcceptRanges, Unit};
+    ///
+    /// let accept_ranges = AcceptRanges::new()
+    ///     .with_unit(Unit::Bytes)
+    ///     .with_unit(Unit::other("pages"));
+    /// assert_eq!(accept_ranges.to_string(), "bytes, pages");
+    /// ```
+    pub fn with_unit(mut self, unit: Unit) -> Self {
+        self.add_unit(unit);
+        self
+    }
+
+    /// Create a new instance from headers.
+    ///
+    /// If multiple `Accept-Ranges` headers are found the last one is used.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(ACCEPT_RANGES) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        // If a header is returned we can assume at least one exists.
+        let s = headers.iter().last().unwrap().as_str();
+        Self::from_str(s).map(Some)
+    }
+
+    /// Sets the `Accept-Ranges` header.
+    ///
+    /// Any existing `Accept-Ranges` header is replaced. Use
+    /// [`append`](#method.append) to merge with the units already
+    /// advertised.
+    pub fn apply(&self, mut headers: impl AsMut<Headers>) {
+        headers.as_mut().insert(ACCEPT_RANGES, self.value());
+    }
+
+    /// Merges the units into the `Accept-Ranges` header.
+    ///
+    /// Units already advertised by an existing `Accept-Ranges` header are
+    /// kept, and the units of `self` not yet listed are added after them.
+    /// The result is written back as a single header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::Response;
+    /// use http_types::range::AcceptRanges;
+    ///
+    /// let mut res = Response::new(200);
+    /// AcceptRanges::with_bytes().append(&mut res);
+    /// AcceptRanges::with_other("pages").append(&mut res);
+    ///
+    /// assert_eq!(res["Accept-Ranges"], "bytes, pages");
+    /// ```
+    pub fn append(&self, mut headers: impl AsMut<Headers>) {
+        let headers = headers.as_mut();
+        let mut merged = Self::new();
+        if let Some(values) = headers.get(ACCEPT_RANGES) {
+            for value in values.iter() {
+                // Parsing `Accept-Ranges` never fails.
+                let existing = Self::from_str(value.as_str()).unwrap();
+                merged.merge(&existing);
+            }
+        }
+        merged.merge(self);
+        merged.apply(headers);
+    }
+
+    /// Get the `HeaderName`.
+    pub fn name(&self) -> HeaderName {
+        ACCEPT_RANGES
+    }
+
+    /// Get the `HeaderValue`.
+    ///
+    /// Multiple units are joined with `, `. When no range requests are
+    /// accepted the value is `none`.
+    ///
+    /// Custom units which are not valid tokens, such as units containing
+    /// whitespace, commas, or line breaks, are left out so they can not split
+    /// or inject headers. Use [`try_value`](#method.try_value) to reject them
+    /// instead.
+    pub fn value(&self) -> HeaderValue {
+        let s = self.to_string();
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(s.into()) }
+    }
+
+    /// Get the `HeaderValue`, or an error if a custom unit is not a valid
+    /// token.
+    pub fn try_value(&self) -> crate::Result<HeaderValue> {
+        if !self.units.iter().all(Unit::is_valid) {
+            return Err(
+                RangeError::Malformed("Invalid Accept-Ranges unit: unit is not a token")
+                    .with_status(StatusCode::InternalServerError),
+            );
+        }
+        Ok(self.value())
+    }
+
+    /// Returns the first accepted range unit, or `None` if no range requests
+    /// are accepted.
+    pub fn unit(&self) -> Option<&Unit> {
+        self.units.first()
+    }
+
+    /// Returns all accepted range units.
+    pub fn units(&self) -> &[Unit] {
+        &self.units
+    }
+
+    /// Add a range unit, unless it is already listed.
+    ///
+    /// Custom units which can not be advertised are ignored, the same way the
+    /// parser ignores them: `none`, and names which are not valid tokens.
+    /// Such units do not clear the `none` state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::{AcceptRanges, Unit};
+    ///
+    /// let mut accept_ranges = AcceptRanges::new();
+    /// accept_ranges.add_unit(Unit::other("a b"));
+    /// assert!(accept_ranges.is_none());
+    /// accept_ranges.add_unit(Unit::other("pages"));
+    /// assert_eq!(accept_ranges.to_string(), "pages");
+    /// ```
+    pub fn add_unit(&mut self, unit: Unit) {
+        if is_advertisable(&unit) && !self.units.contains(&unit) {
+            self.units.push(unit);
+        }
+    }
+
+    /// Remove a range unit. Once all units are removed, no range requests are
+    /// accepted.
+    pub fn remove_unit(&mut self, unit: &Unit) {
+        self.units.retain(|u| u != unit);
+    }
+
+    /// Returns `true` if no range requests are accepted.
+    pub fn is_none(&self) -> bool {
+        self.units.is_empty()
+    }
+
+    /// Returns `true` if the `bytes` range unit is accepted.
+    pub fn supports_bytes(&self) -> bool {
+        self.units.contains(&Unit::Bytes)
+    }
+
+    /// Returns the first of the `preferred` units accepted by the server, or
+    /// `None` if none of them are accepted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::{AcceptRanges, Unit};
+    ///
+    /// let accept_ranges: AcceptRanges = "bytes, pages".parse()?;
+    /// let preferred = [Unit::other("pages"), Unit::Bytes];
+    /// assert_eq!(accept_ranges.negotiate(&preferred), Some(Unit::other("pages")));
+    ///
+    /// let accept_ranges = AcceptRanges::new();
+    /// assert_eq!(accept_ranges.negotiate(&preferred), None);
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn negotiate(&self, preferred: &[Unit]) -> Option<Unit> {
+        preferred
+            .iter()
+            .find(|unit| self.units.contains(unit))
+            .cloned()
+    }
+
+    /// Add the units of `other` not yet listed.
+    fn merge(&mut self, other: &Self) {
+        for unit in &other.units {
+            self.add_unit(unit.clone());
+        }
+    }
+
+    /// Returns the first custom range unit, if any.
+    pub fn other(&self) -> Option<&str> {
+        self.units.iter().find_map(|unit| match unit {
+            Unit::Other(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+}
+
+impl FromStr for AcceptRanges {
+    type Err = crate::Error;
+
+    /// Parse a comma-separated list of range units.
+    ///
+    /// Whitespace around entries is trimmed, and empty entries and entries
+    /// which are not valid tokens are ignored, so an empty value is
+    /// equivalent to `none`.
+    /// The `none` token is only meaningful on its own: if it is listed next to
+    /// actual range units, the actual units take precedence and `none` is
+    /// ignored.
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let units = s
+            .split(',')
+            .filter_map(|part| parse_unit(part.trim()))
+            .collect();
+        Ok(Self { units })
+    }
+}
+
+/// Read a single entry of the header, ignoring `none` and invalid names.
+fn parse_unit(name: &str) -> Option<Unit> {
+    if !is_token(name) {
+        return None;
+    }
+    Some(Unit::from(name)).filter(is_advertisable)
+}
+
+/// Returns `true` if the unit can be listed in the header, and parsed back.
+fn is_advertisable(unit: &Unit) -> bool {
+    match unit {
+        Unit::Bytes => true,
+        Unit::Other(name) => unit.is_valid() && !name.eq_ignore_ascii_case("none"),
+    }
+}
+
+impl From<Unit> for AcceptRanges {
+    /// Convert from a unit. Units which can not be advertised accept no range
+    /// requests, see [`add_unit`](#method.add_unit).
+    fn from(unit: Unit) -> Self {
+        Self::new().with_unit(unit)
+    }
+}
+
+impl From<Option<Unit>> for AcceptRanges {
+    /// Convert from an optional unit, where `None` accepts no range requests.
+    fn from(unit: Option<Unit>) -> Self {
+        unit.map_or_else(Self::new, Self::from)
+    }
+}
+
+impl Display for AcceptRanges {
+    /// Format the units as a comma-separated list, or as `none` if no range
+    /// requests are accepted.
+    ///
+    /// Custom units which are not valid tokens are left out.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut units = self.units.iter().filter(|unit| unit.is_valid());
+        match units.next() {
+            Some(unit) => write!(f, "{}", unit)?,
+            None => return write!(f, "none"),
+        }
+        for unit in units {
+            write!(f, ", {}", unit)?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq<str> for AcceptRanges {
+    fn eq(&self, other: &str) -> bool {
+        self.to_string().as_str() == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for AcceptRanges {
+    fn eq(&self, other: &&'a str) -> bool {
+        self == *other
+    }
+}
+
+impl ToHeaderValues for AcceptRanges {
+    type Iter = option::IntoIter<HeaderValue>;
+    fn to_header_values(&self) -> crate::Result<Self::Iter> {
+        self.try_value()?.to_header_values()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headers::Headers;
+
+    #[test]
+    fn smoke() -> crate::Result<()> {
+        let accept_ranges = AcceptRanges::with_bytes();
+
+        let mut headers = Headers::new();
+        accept_ranges.apply(&mut headers);
+
+        assert_eq!(headers[ACCEPT_RANGES], "bytes");
+        let accept_ranges = AcceptRanges::from_headers(headers)?.unwrap();
+        assert!(accept_ranges.supports_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn none() -> crate::Result<()> {
+        let accept_ranges = AcceptRanges::new();
+
+        let mut headers = Headers::new();
+        accept_ranges.apply(&mut headers);
+
+        assert_eq!(headers[ACCEPT_RANGES], "none");
+        let accept_ranges = AcceptRanges::from_headers(headers)?.unwrap();
+        assert!(accept_ranges.is_none());
+        assert!(!accept_ranges.supports_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn add_and_remove_units() {
+        let mut accept_ranges = AcceptRanges::new();
+        assert_eq!(accept_ranges, "none");
+
+        accept_ranges.add_unit(Unit::Bytes);
+        accept_ranges.add_unit(Unit::Bytes);
+        accept_ranges.add_unit(Unit::other("pages"));
+        assert!(!accept_ranges.is_none());
+        assert_eq!(accept_ranges, "bytes, pages");
+
+        accept_ranges.remove_unit(&Unit::Bytes);
+        assert_eq!(accept_ranges, "pages");
+        accept_ranges.remove_unit(&Unit::other("pages"));
+        assert!(accept_ranges.is_none());
+        assert_eq!(accept_ranges, "none");
+
+        let accept_ranges = AcceptRanges::new().with_unit(Unit::other("pages"));
+        assert_eq!(accept_ranges.units(), &[Unit::other("pages")]);
+    }
+
+    #[test]
+    fn case_insensitive_units() -> crate::Result<()> {
+        for s in &["Bytes", "BYTES", "bYtEs"] {
+            let accept_ranges = AcceptRanges::from_str(s)?;
+            assert!(accept_ranges.supports_bytes());
+            assert_eq!(accept_ranges, "bytes");
+        }
+        assert!(AcceptRanges::from_str("NONE")?.is_none());
+        assert_eq!(AcceptRanges::from_str("Pages")?, "pages");
+        Ok(())
+    }
+
+    #[test]
+    fn eq_str() {
+        assert_eq!(AcceptRanges::with_bytes(), "bytes");
+        assert_eq!(AcceptRanges::new(), *"none");
+        assert_ne!(AcceptRanges::with_other("pages"), "bytes");
+    }
+
+    #[test]
+    fn empty_value() -> crate::Result<()> {
+        for value in &["", "  ", ", ,"] {
+            let mut headers = Headers::new();
+            headers.insert(ACCEPT_RANGES, *value);
+
+            let accept_ranges = AcceptRanges::from_headers(headers)?.unwrap();
+            assert!(accept_ranges.is_none());
+            assert!(accept_ranges.units().is_empty());
+            assert_eq!(accept_ranges.to_string(), "none");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn other() -> crate::Result<()> {
+        let accept_ranges = AcceptRanges::with_other("pages");
+
+        let mut headers = Headers::new();
+        accept_ranges.apply(&mut headers);
+
+        assert_eq!(headers[ACCEPT_RANGES], "pages");
+        let accept_ranges = AcceptRanges::from_headers(headers)?.unwrap();
+        assert_eq!(accept_ranges.other(), Some("pages"));
+        Ok(())
+    }
+
+    #[test]
+    fn append() {
+        let mut headers = Headers::new();
+        AcceptRanges::with_bytes().append(&mut headers);
+        assert_eq!(headers[ACCEPT_RANGES], "bytes");
+
+        AcceptRanges::from_str("pages, bytes")
+            .unwrap()
+            .append(&mut headers);
+        assert_eq!(headers[ACCEPT_RANGES], "bytes, pages");
+
+        AcceptRanges::new().append(&mut headers);
+        assert_eq!(headers[ACCEPT_RANGES], "bytes, pages");
+
+        AcceptRanges::with_other("rows").apply(&mut headers);
+        assert_eq!(headers[ACCEPT_RANGES], "rows");
+
+        let mut headers = Headers::new();
+        AcceptRanges::new().append(&mut headers);
+        assert_eq!(headers[ACCEPT_RANGES], "none");
+    }
+
+    #[test]
+    fn from_unit() {
+        assert_eq!(AcceptRanges::from(Unit::Bytes), AcceptRanges::with_bytes());
+        assert_eq!(
+            AcceptRanges::from(Unit::Other("pages".into())),
+            AcceptRanges::with_other("pages")
+        );
+        assert_eq!(
+            AcceptRanges::from(Some(Unit::Bytes)),
+            AcceptRanges::with_bytes()
+        );
+        assert_eq!(AcceptRanges::from(None), AcceptRanges::new());
+    }
+
+    #[test]
+    fn negotiate() -> crate::Result<()> {
+        let accept_ranges = AcceptRanges::from_str("bytes, pages")?;
+        assert_eq!(accept_ranges.negotiate(&[Unit::Bytes]), Some(Unit::Bytes));
+        assert_eq!(
+            accept_ranges.negotiate(&[Unit::other("rows"), Unit::other("pages")]),
+            Some(Unit::other("pages"))
+        );
+        assert_eq!(accept_ranges.negotiate(&[Unit::other("rows")]), None);
+        assert_eq!(accept_ranges.negotiate(&[]), None);
+        assert_eq!(AcceptRanges::new().negotiate(&[Unit::Bytes]), None);
+        Ok(())
+    }
+
+    #[test]
+    fn header_injection() {
+        let accept_ranges = AcceptRanges::with_other("a\r\nEvil: x");
+        assert!(accept_ranges.is_none());
+        assert_eq!(accept_ranges.value(), "none");
+
+        let accept_ranges = AcceptRanges::new().with_unit(Unit::other("a\r\nEvil: x"));
+        assert!(accept_ranges.is_none());
+        assert_eq!(accept_ranges.value(), "none");
+
+        // Invalid units can only be stored by building the struct directly.
+        let accept_ranges = AcceptRanges {
+            units: vec![Unit::other("a\r\nEvil: x")],
+        };
+        assert_eq!(accept_ranges.to_string(), "none");
+        assert_eq!(accept_ranges.value(), "none");
+        assert_eq!(accept_ranges.try_value().unwrap_err().status(), 500);
+        assert!(accept_ranges.to_header_values().is_err());
+
+        let mut accept_ranges = AcceptRanges::with_bytes();
+        accept_ranges.add_unit(Unit::other("a, b"));
+        accept_ranges.merge(&AcceptRanges::with_other("pages"));
+        assert_eq!(accept_ranges.to_string(), "bytes, pages");
+        assert!(accept_ranges.try_value().is_ok());
+    }
+
+    #[test]
+    fn unadvertisable_units_keep_none() -> crate::Result<()> {
+        for unit in &[
+            Unit::other("a b"),
+            Unit::other("none"),
+            Unit::other("NONE"),
+            Unit::other(""),
+        ] {
+            let built = AcceptRanges::new().with_unit(unit.clone());
+            let converted = AcceptRanges::from(unit.clone());
+            let optional = AcceptRanges::from(Some(unit.clone()));
+            for accept_ranges in &[built, converted, optional] {
+                assert!(accept_ranges.is_none(), "{:?}", unit);
+                assert!(accept_ranges.units().is_empty());
+                assert_eq!(accept_ranges.negotiate(std::slice::from_ref(unit)), None);
+                assert_eq!(accept_ranges.to_string(), "none");
+                let reparsed = AcceptRanges::from_str(&accept_ranges.to_string())?;
+                assert_eq!(&reparsed, accept_ranges);
+            }
+        }
+
+        let accept_ranges = AcceptRanges::with_bytes().with_unit(Unit::other("a b"));
+        assert_eq!(accept_ranges.units(), &[Unit::Bytes]);
+        Ok(())
+    }
+
+    #[test]
+    fn empty_and_invalid_custom_units() -> crate::Result<()> {
+        for name in &["", "none", "NONE", "a b", "a\tb", "é"] {
+            assert!(AcceptRanges::with_other(name).is_none(), "{:?}", name);
+            assert!(AcceptRanges::from_str(name)?.is_none(), "{:?}", name);
+        }
+        assert!(AcceptRanges::with_other("a, b").is_none());
+        assert_eq!(
+            AcceptRanges::with_other("Pages").units(),
+            &[Unit::other("pages")]
+        );
+
+        let accept_ranges = AcceptRanges::from_str("bytes, a b, pages")?;
+        assert_eq!(accept_ranges.units(), &[Unit::Bytes, Unit::other("pages")]);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_list() -> crate::Result<()> {
+        let accept_ranges = AcceptRanges::from_str("bytes, pages")?;
+        assert_eq!(
+            accept_ranges.units(),
+            &[Unit::Bytes, Unit::Other(String::from("pages"))]
+        );
+
+        let accept_ranges = AcceptRanges::from_str(" bytes ,")?;
+        assert_eq!(accept_ranges.units(), &[Unit::Bytes]);
+
+        let accept_ranges = AcceptRanges::from_str(",,\tpages ,  ")?;
+        assert_eq!(accept_ranges.units(), &[Unit::Other(String::from("pages"))]);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_none_with_units() -> crate::Result<()> {
+        let accept_ranges = AcceptRanges::from_str("bytes, none")?;
+        assert!(!accept_ranges.is_none());
+        assert_eq!(accept_ranges.units(), &[Unit::Bytes]);
+
+        let accept_ranges = AcceptRanges::from_str("none, none")?;
+        assert!(accept_ranges.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn display_list() -> crate::Result<()> {
+        let accept_ranges = AcceptRanges::from_str("bytes,pages")?;
+        assert_eq!(accept_ranges.to_string(), "bytes, pages");
+        assert_eq!(accept_ranges.value(), "bytes, pages");
+
+        let roundtrip = AcceptRanges::from_str(&accept_ranges.to_string())?;
+        assert_eq!(roundtrip, accept_ranges);
+        assert_eq!(roundtrip.to_string(), "bytes, pages");
+        Ok(())
+    }
+
+    #[test]
+    fn display_none() -> crate::Result<()> {
+        assert_eq!(AcceptRanges::new().to_string(), "none");
+        assert_eq!(AcceptRanges::from_str("none")?.value(), "none");
+        Ok(())
+    }
+}
@@ -0,0 +1,1006 @@
+use crate::headers::{HeaderName, HeaderValue, Headers, ToHeaderValues, CONTENT_RANGE};
+use crate::range::{ByteRange, RangeError};
+use crate::StatusCode;
+
+use std::fmt::{self, Display};
+use std::option;
+use std::str::FromStr;
+
+/// HTTP Content-Range response header.
+///
+/// The `Content-Range` header indicates where in a full body a partial
+/// message belongs. Only the `bytes` range unit is supported.
+///
+/// # Specifications
+///
+/// - [RFC 7233, section 4.2: Content-Range](https://tools.ietf.org/html/rfc7233#section-4.2)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Response;
+/// use http_types::range::ByteContentRange;
+///
+/// let content_range = ByteContentRange::new().with_range(0, 499).with_size(1000);
+///
+/// let mut res = Response::new(206);
+/// content_range.apply(&mut res);
+///
+/// let content_range = ByteContentRange::from_headers(res)?.unwrap();
+/// assert_eq!(content_range.range(), Some((0, 499)));
+/// assert_eq!(content_range.size(), Some(1000));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ByteContentRange {
+    range: Option<(u64, u64)>,
+    size: Option<u64>,
+}
+
+impl ByteContentRange {
+    /// Create a new instance with no range and an unknown size.
+    pub fn new() -> Self {
+        Self {
+            range: None,
+            size: None,
+        }
+    }
+
+    /// Create a new instance for a `416 Requested Range Not Satisfiable`
+    /// response, carrying only the complete size of the document.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteContentRange;
+    ///
+    /// let content_range = ByteContentRange::unsatisfied(1000);
+    /// assert_eq!(content_range.to_string(), "bytes */1000");
+    /// assert!(content_range.is_unsatisfied());
+    /// ```
+    pub fn unsatisfied(size: u64) -> Self {
+        Self::new().with_size(size)
+    }
+
+    /// Set the inclusive range of bytes this message contains.
+    pub fn with_range(mut self, start: u64, end: u64) -> Self {
+        self.range = Some((start, end));
+        self
+    }
+
+    /// Set the inclusive range of bytes this message contains from a
+    /// `ByteRange`.
+    ///
+    /// Returns a `416 Requested Range Not Satisfiable` error if the range is
+    /// a suffix or open-ended range, as those need to be resolved against the
+    /// document size first.
+    pub fn with_byte_range(self, range: ByteRange) -> crate::Result<Self> {
+        match (range.start, range.end) {
+            (Some(start), Some(end)) => Ok(self.with_range(start, end)),
+            _ => Err(RangeError::Unsatisfiable(
+                "Content-Range requires a range with concrete bounds",
+            )
+            .into_error()),
+        }
+    }
+
+    /// Create a new instance for a fully bounded range of a document of the
+    /// given size.
+    ///
+    /// Returns a `416 Requested Range Not Satisfiable` error if the range is
+    /// a suffix or open-ended range, or if it ends past the document, and a
+    /// `400 Bad Request` error if it is reversed. This is the same as
+    /// [`with_byte_range`](#method.with_byte_range) followed by
+    /// [`try_build`](#method.try_build).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::{ByteContentRange, ByteRange};
+    ///
+    /// let content_range = ByteContentRange::for_range_and_size(ByteRange::new(0, 499), 1000);
+    /// assert_eq!(content_range.unwrap().to_string(), "bytes 0-499/1000");
+    ///
+    /// let err = ByteContentRange::for_range_and_size(ByteRange::new(0, 1000), 1000).unwrap_err();
+    /// assert_eq!(err.status(), 416);
+    /// ```
+    pub fn for_range_and_size(range: ByteRange, size: u64) -> crate::Result<Self> {
+        Self::new()
+            .with_byte_range(range)?
+            .with_size(size)
+            .try_build()
+    }
+
+    /// Set the complete size of the document.
+    pub fn with_size(mut self, size: u64) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Validate the range against the complete size of the document.
+    ///
+    /// The builder methods do not check that the range fits in the document,
+    /// so a value built with them may not parse back. This returns an error if
+    /// the range start is greater than its end, if the range end is not
+    /// smaller than the complete size, or if neither a range nor a complete
+    /// size is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteContentRange;
+    ///
+    /// assert!(ByteContentRange::new().with_range(0, 9).with_size(10).try_build().is_ok());
+    /// assert!(ByteContentRange::new().with_range(0, 10).with_size(10).try_build().is_err());
+    /// ```
+    pub fn try_build(self) -> crate::Result<Self> {
+        if self.range.is_none() && self.size.is_none() {
+            return Err(RangeError::Malformed(
+                "Content-Range requires a range or a complete length",
+            )
+            .into_error());
+        }
+        if let Some((start, end)) = self.range {
+            if start > end {
+                return Err(RangeError::Reversed.into_error());
+            }
+            if let Some(size) = self.size {
+                if size <= end {
+                    return Err(RangeError::Unsatisfiable(
+                        "Content-Range end exceeds the complete length",
+                    )
+                    .into_error());
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// Returns `true` if the value is internally consistent, and can be
+    /// applied and parsed back.
+    ///
+    /// This is the non-failing counterpart of
+    /// [`try_build`](#method.try_build).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteContentRange;
+    ///
+    /// assert!(ByteContentRange::new().with_range(0, 9).with_size(10).is_valid());
+    /// assert!(!ByteContentRange::new().with_range(0, 10).with_size(10).is_valid());
+    /// assert!(!ByteContentRange::new().is_valid());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        self.try_build().is_ok()
+    }
+
+    /// Returns the inclusive range of bytes, or `None` if the range is
+    /// unsatisfied.
+    pub fn range(&self) -> Option<(u64, u64)> {
+        self.range
+    }
+
+    /// Returns `true` if the range is unsatisfied and the complete size of the
+    /// document is known, as sent in a `416 Requested Range Not Satisfiable`
+    /// response.
+    pub fn is_unsatisfied(&self) -> bool {
+        self.range.is_none() && self.size.is_some()
+    }
+
+    /// Returns the range of bytes as a `ByteRange`, or `None` if the range is
+    /// unsatisfied.
+    pub fn byte_range(&self) -> Option<ByteRange> {
+        self.range.map(|(start, end)| ByteRange::new(start, end))
+    }
+
+    /// Returns the complete size of the document, or `None` if it is unknown.
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// Returns the complete length of the document, or `None` if it is
+    /// unknown.
+    ///
+    /// This is an alias for [`size`](#method.size) using the RFC 7233
+    /// terminology.
+    pub fn complete_length(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// Returns `true` if the complete size of the document is known, and
+    /// `false` if it was reported as `*`.
+    pub fn size_known(&self) -> bool {
+        self.size.is_some()
+    }
+
+    /// Returns the number of bytes covered by the range, `end - start + 1`.
+    ///
+    /// Returns `None` if the range is unsatisfied, or if it is reversed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteContentRange;
+    ///
+    /// let content_range: ByteContentRange = "bytes 0-4/10".parse()?;
+    /// assert_eq!(content_range.length(), Some(5));
+    ///
+    /// let content_range: ByteContentRange = "bytes */10".parse()?;
+    /// assert_eq!(content_range.length(), None);
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn length(&self) -> Option<u64> {
+        let (start, end) = self.range?;
+        end.checked_sub(start)?.checked_add(1)
+    }
+
+    /// Returns the `Content-Length` of a single range response, which is the
+    /// [`length`](#method.length) of the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteContentRange;
+    ///
+    /// let content_range = ByteContentRange::new().with_range(0, 499).with_size(1000);
+    /// assert_eq!(content_range.content_length(), Some(500));
+    /// assert_eq!(ByteContentRange::new().with_size(1000).content_length(), None);
+    /// ```
+    pub fn content_length(&self) -> Option<u64> {
+        self.length()
+    }
+
+    /// Check that the range length matches the length of the body being
+    /// served.
+    ///
+    /// Returns a `500 Internal Server Error` if the range is unsatisfied, or
+    /// if `end - start + 1` differs from `body_len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteContentRange;
+    ///
+    /// let content_range = ByteContentRange::new().with_range(0, 499).with_size(1000);
+    /// assert!(content_range.validate_body_len(500).is_ok());
+    /// assert!(content_range.validate_body_len(499).is_err());
+    /// ```
+    pub fn validate_body_len(&self, body_len: u64) -> crate::Result<()> {
+        // Without a range, `length` is `None` and never matches.
+        if self.length() != Some(body_len) {
+            return Err(RangeError::BodyLengthMismatch.into_error());
+        }
+        Ok(())
+    }
+
+    /// Suggest a satisfiable range of up to `desired_len` bytes, starting at
+    /// the beginning of the document.
+    ///
+    /// This is meant to be used by clients receiving a `416 Requested Range
+    /// Not Satisfiable` response with a `bytes */size` header, to retry with a
+    /// valid range. Returns `None` if the complete size is unknown, or if
+    /// either the size or `desired_len` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::{ByteContentRange, ByteRange};
+    ///
+    /// let content_range: ByteContentRange = "bytes */1500".parse()?;
+    /// assert_eq!(content_range.satisfiable_range(1000), Some(ByteRange::new(0, 999)));
+    /// assert_eq!(content_range.satisfiable_range(2000), Some(ByteRange::new(0, 1499)));
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn satisfiable_range(&self, desired_len: u64) -> Option<ByteRange> {
+        let size = self.size?;
+        let len = size.min(desired_len);
+        if len == 0 {
+            return None;
+        }
+        Some(ByteRange::new(0, len - 1))
+    }
+
+    /// Create a new instance from headers.
+    ///
+    /// Returns `None` if the `Content-Range` header is missing. A response
+    /// carrying multiple `Content-Range` headers is malformed, and returns a
+    /// `400 Bad Request` error rather than trusting any one of them.
+    ///
+    /// If the header uses a range unit other than `bytes`, an error wrapping
+    /// [`RangeError::InvalidUnit`] is returned.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(CONTENT_RANGE) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        // If a header is returned we can assume at least one exists.
+        let mut values = headers.iter();
+        let s = values.next().unwrap().as_str();
+        if values.next().is_some() {
+            return Err(RangeError::Malformed(
+                "Invalid Content-Range header: multiple headers found",
+            )
+            .into_error());
+        }
+        Self::from_str(s).map(Some)
+    }
+
+    /// Sets the `Content-Range` header.
+    ///
+    /// No validation is performed: a range exceeding the complete size will be
+    /// serialized as-is and rejected when parsed back. Use
+    /// [`try_build`](#method.try_build) to validate beforehand.
+    pub fn apply(&self, mut headers: impl AsMut<Headers>) {
+        headers.as_mut().insert(CONTENT_RANGE, self.value());
+    }
+
+    /// Get the `HeaderName`.
+    pub fn name(&self) -> HeaderName {
+        CONTENT_RANGE
+    }
+
+    /// Get the `HeaderValue`.
+    ///
+    /// The value is always a single line of visible ASCII characters and
+    /// spaces, without any CR, LF or other control characters. It can be
+    /// written into a hand-built header block as is.
+    pub fn value(&self) -> HeaderValue {
+        let s = self.to_string();
+        debug_assert!(
+            s.bytes().all(|b| b == b' ' || b.is_ascii_graphic()),
+            "Content-Range values must be a single line of visible ASCII"
+        );
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(s.into()) }
+    }
+
+    /// Render the header block of a `multipart/byteranges` body part.
+    ///
+    /// The returned string contains the `Content-Type` and `Content-Range`
+    /// headers of the part, followed by the empty line separating the headers
+    /// from the part body.
+    ///
+    /// Returns a `500 Internal Server Error` if the content type contains CR,
+    /// LF or any other control character, which would allow injecting
+    /// headers into the part.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteContentRange;
+    ///
+    /// let content_range = ByteContentRange::new().with_range(0, 499).with_size(1000);
+    /// assert_eq!(
+    ///     content_range.part_headers("text/plain")?,
+    ///     "Content-Type: text/plain\r\nContent-Range: bytes 0-499/1000\r\n\r\n"
+    /// );
+    /// assert!(content_range.part_headers("text/plain\r\nEvil: x").is_err());
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn part_headers(&self, content_type: &str) -> crate::Result<String> {
+        if content_type.chars().any(|c| c.is_control() && c != '\t') {
+            return Err(RangeError::Malformed(
+                "Invalid Content-Type: control characters are not allowed",
+            )
+            .with_status(StatusCode::InternalServerError));
+        }
+        Ok(format!(
+            "Content-Type: {}\r\nContent-Range: {}\r\n\r\n",
+            content_type, self
+        ))
+    }
+}
+
+impl FromStr for ByteContentRange {
+    type Err = crate::Error;
+
+    /// Parse a `bytes` prefixed `Content-Range` value.
+    ///
+    /// Whitespace is only allowed around the value and after the unit, as
+    /// RFC 7233 does not allow it anywhere else: `bytes 1-5 / 10` is rejected.
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let s = match strip_unit(s.trim()) {
+            Some(s) => s,
+            None => return Err(RangeError::InvalidUnit.into_error()),
+        };
+        if s.contains(&[' ', '\t'][..]) {
+            return Err(RangeError::Malformed(
+                "Invalid Content-Range header: unexpected whitespace",
+            )
+            .into_error());
+        }
+
+        let mut parts = s.split('/');
+        let range = parts.next().unwrap_or("");
+        let size = match parts.next() {
+            Some(size) => size,
+            None => {
+                return Err(RangeError::Malformed(
+                    "Invalid Content-Range header: missing complete length",
+                )
+                .into_error())
+            }
+        };
+        if parts.next().is_some() {
+            return Err(RangeError::Malformed(
+                "Invalid Content-Range header: too many '/' separators",
+            )
+            .into_error());
+        }
+
+        let size = match size {
+            "*" => None,
+            size => Some(parse_u64(size)?),
+        };
+
+        let range = match range {
+            "*" => None,
+            range => {
+                let mut bounds = range.splitn(2, '-');
+                let start = bounds.next().unwrap_or("");
+                let end = bounds.next().ok_or_else(|| {
+                    RangeError::Malformed("Invalid Content-Range header: missing range separator")
+                        .into_error()
+                })?;
+                let start = parse_u64(start)?;
+                let end = parse_u64(end)?;
+                if start > end {
+                    return Err(RangeError::Reversed.into_error());
+                }
+                Some((start, end))
+            }
+        };
+
+        if range.is_none() && size.is_none() {
+            return Err(RangeError::Malformed(
+                "Content-Range requires a range or a complete length",
+            )
+            .into_error());
+        }
+        if let (Some((_, end)), Some(size)) = (range, size) {
+            if size <= end {
+                return Err(RangeError::Unsatisfiable(
+                    "Content-Range end exceeds the complete length",
+                )
+                .into_error());
+            }
+        }
+
+        Ok(Self { range, size })
+    }
+}
+
+/// Strip the case-insensitive `bytes` unit and the whitespace following it.
+fn strip_unit(s: &str) -> Option<&str> {
+    if !s.get(..5)?.eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+    let rest = &s[5..];
+    if !rest.starts_with(&[' ', '\t'][..]) {
+        return None;
+    }
+    Some(rest.trim_start())
+}
+
+fn parse_u64(s: &str) -> crate::Result<u64> {
+    let err = || {
+        RangeError::Malformed("Invalid Content-Range header: value is not a number").into_error()
+    };
+    // `u64::from_str` accepts a leading `+`, which RFC 7233 does not allow.
+    if !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(err());
+    }
+    s.parse().map_err(|_| err())
+}
+
+impl Display for ByteContentRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bytes ")?;
+        match self.range {
+            Some((start, end)) => write!(f, "{}-{}", start, end)?,
+            None => write!(f, "*")?,
+        }
+        match self.size {
+            Some(size) => write!(f, "/{}", size),
+            None => write!(f, "/*"),
+        }
+    }
+}
+
+impl PartialEq<str> for ByteContentRange {
+    fn eq(&self, other: &str) -> bool {
+        self.to_string().as_str() == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for ByteContentRange {
+    fn eq(&self, other: &&'a str) -> bool {
+        self == *other
+    }
+}
+
+impl From<&ByteContentRange> for Option<ByteRange> {
+    fn from(content_range: &ByteContentRange) -> Self {
+        content_range.byte_range()
+    }
+}
+
+impl ToHeaderValues for ByteContentRange {
+    type Iter = option::IntoIter<HeaderValue>;
+    fn to_header_values(&self) -> crate::Result<Self::Iter> {
+        self.value().to_header_values()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headers::Headers;
+
+    #[test]
+    fn smoke() -> crate::Result<()> {
+        let content_range = ByteContentRange::new().with_range(1, 5).with_size(10);
+
+        let mut headers = Headers::new();
+        content_range.apply(&mut headers);
+
+        assert_eq!(headers[CONTENT_RANGE], "bytes 1-5/10");
+        let content_range = ByteContentRange::from_headers(headers)?.unwrap();
+        assert_eq!(content_range.range(), Some((1, 5)));
+        assert_eq!(content_range.size(), Some(10));
+        assert_eq!(content_range.complete_length(), Some(10));
+        assert!(content_range.size_known());
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_size() -> crate::Result<()> {
+        let content_range = ByteContentRange::new().with_range(1, 5);
+
+        let mut headers = Headers::new();
+        content_range.apply(&mut headers);
+
+        assert_eq!(headers[CONTENT_RANGE], "bytes 1-5/*");
+        let content_range = ByteContentRange::from_headers(headers)?.unwrap();
+        assert_eq!(content_range.range(), Some((1, 5)));
+        assert_eq!(content_range.size(), None);
+        assert_eq!(content_range.complete_length(), None);
+        assert!(!content_range.size_known());
+        Ok(())
+    }
+
+    #[test]
+    fn unsatisfied_range() -> crate::Result<()> {
+        let content_range = ByteContentRange::new().with_size(10);
+
+        let mut headers = Headers::new();
+        content_range.apply(&mut headers);
+
+        assert_eq!(headers[CONTENT_RANGE], "bytes */10");
+        let content_range = ByteContentRange::from_headers(headers)?.unwrap();
+        assert_eq!(content_range.range(), None);
+        assert_eq!(content_range.size(), Some(10));
+        Ok(())
+    }
+
+    #[test]
+    fn with_byte_range() -> crate::Result<()> {
+        let content_range = ByteContentRange::new()
+            .with_byte_range(ByteRange::new(1, 5))?
+            .with_size(10);
+        assert_eq!(
+            content_range,
+            ByteContentRange::new().with_range(1, 5).with_size(10)
+        );
+
+        let err = ByteContentRange::new()
+            .with_byte_range(ByteRange::new(None, 5))
+            .unwrap_err();
+        assert_eq!(err.status(), 416);
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Unsatisfiable(
+                "Content-Range requires a range with concrete bounds"
+            ))
+        );
+        let err = ByteContentRange::new()
+            .with_byte_range(ByteRange::new(1, None))
+            .unwrap_err();
+        assert_eq!(err.status(), 416);
+        Ok(())
+    }
+
+    #[test]
+    fn try_build() {
+        let content_range = ByteContentRange::new().with_range(1, 5);
+        assert!(content_range.try_build().is_ok());
+        assert!(content_range.with_size(6).try_build().is_ok());
+
+        let err = content_range.with_size(5).try_build().unwrap_err();
+        assert_eq!(err.status(), 416);
+        let err = ByteContentRange::new()
+            .with_range(5, 1)
+            .try_build()
+            .unwrap_err();
+        assert_eq!(err.status(), 400);
+        let err = ByteContentRange::new().try_build().unwrap_err();
+        assert_eq!(err.status(), 400);
+    }
+
+    #[test]
+    fn is_valid() {
+        assert!(ByteContentRange::new().with_range(1, 5).is_valid());
+        assert!(ByteContentRange::new().with_size(10).is_valid());
+        assert!(ByteContentRange::new()
+            .with_range(1, 5)
+            .with_size(6)
+            .is_valid());
+
+        assert!(!ByteContentRange::new().is_valid());
+        assert!(!ByteContentRange::new()
+            .with_range(1, 5)
+            .with_size(5)
+            .is_valid());
+        assert!(!ByteContentRange::new()
+            .with_range(1, 5)
+            .with_size(0)
+            .is_valid());
+        assert!(!ByteContentRange::new().with_range(5, 1).is_valid());
+        assert!(!ByteContentRange::new()
+            .with_range(5, 1)
+            .with_size(10)
+            .is_valid());
+    }
+
+    #[test]
+    fn satisfiable_range() {
+        let content_range = ByteContentRange::new().with_size(10);
+        assert_eq!(
+            content_range.satisfiable_range(5),
+            Some(ByteRange::new(0, 4))
+        );
+        assert_eq!(
+            content_range.satisfiable_range(50),
+            Some(ByteRange::new(0, 9))
+        );
+        assert_eq!(content_range.satisfiable_range(0), None);
+        assert_eq!(
+            ByteContentRange::new().with_size(0).satisfiable_range(5),
+            None
+        );
+        assert_eq!(ByteContentRange::new().satisfiable_range(5), None);
+    }
+
+    #[test]
+    fn unit_must_be_followed_by_whitespace() -> crate::Result<()> {
+        let err = ByteContentRange::from_str("bytesfoo 1-5/10").unwrap_err();
+        assert_eq!(err.status(), 400);
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::InvalidUnit)
+        );
+        assert!(ByteContentRange::from_str("bytes1-5/10").is_err());
+
+        let mut headers = Headers::new();
+        headers.insert(CONTENT_RANGE, "bytesfoo 1-5/10");
+        assert!(ByteContentRange::from_headers(headers).is_err());
+
+        let content_range = ByteContentRange::from_str("bytes \t 1-5/10")?;
+        assert_eq!(content_range.range(), Some((1, 5)));
+        Ok(())
+    }
+
+    #[test]
+    fn byte_range() -> crate::Result<()> {
+        let content_range = ByteContentRange::from_str("bytes 1-5/10")?;
+        assert_eq!(content_range.byte_range(), Some(ByteRange::new(1, 5)));
+        let range: Option<ByteRange> = (&content_range).into();
+        assert_eq!(range, Some(ByteRange::new(1, 5)));
+
+        let content_range = ByteContentRange::from_str("bytes */10")?;
+        assert_eq!(content_range.byte_range(), None);
+        assert_eq!(Option::<ByteRange>::from(&content_range), None);
+        Ok(())
+    }
+
+    #[test]
+    fn part_headers() -> crate::Result<()> {
+        let content_range = ByteContentRange::new().with_range(500, 999);
+        assert_eq!(
+            content_range.part_headers("application/octet-stream")?,
+            "Content-Type: application/octet-stream\r\nContent-Range: bytes 500-999/*\r\n\r\n"
+        );
+        assert!(content_range
+            .part_headers("text/plain;\tcharset=utf-8")
+            .is_ok());
+
+        for content_type in &["text/plain\r\nEvil: x", "text/plain\n", "text/\0plain"] {
+            let err = content_range.part_headers(content_type).unwrap_err();
+            assert_eq!(err.status(), 500);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn case_insensitive_unit() -> crate::Result<()> {
+        for s in &["Bytes 1-5/10", "BYTES 1-5/10", "bYtEs 1-5/10"] {
+            let content_range = ByteContentRange::from_str(s)?;
+            assert_eq!(content_range.range(), Some((1, 5)));
+            assert_eq!(content_range, "bytes 1-5/10");
+        }
+        assert!(ByteContentRange::from_str("BYTE 1-5/10").is_err());
+        assert!(ByteContentRange::from_str("Bytés 1-5/10").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn multiple_headers_are_an_error() -> crate::Result<()> {
+        let mut headers = Headers::new();
+        headers.append(CONTENT_RANGE, "bytes 0-4/50");
+        headers.append(CONTENT_RANGE, "bytes 5-9/50");
+        let err = ByteContentRange::from_headers(&headers).unwrap_err();
+        assert_eq!(err.status(), 400);
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Malformed(
+                "Invalid Content-Range header: multiple headers found"
+            ))
+        );
+
+        headers.insert(CONTENT_RANGE, "bytes 5-9/50");
+        let content_range = ByteContentRange::from_headers(&headers)?.unwrap();
+        assert_eq!(content_range.range(), Some((5, 9)));
+        Ok(())
+    }
+
+    #[test]
+    fn other_unit_is_an_error() {
+        let mut headers = Headers::new();
+        headers.insert(CONTENT_RANGE, "items 0-4/50");
+        let err = ByteContentRange::from_headers(headers).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::InvalidUnit)
+        );
+
+        assert_eq!(
+            ByteContentRange::from_headers(Headers::new()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn validate_body_len() {
+        let content_range = ByteContentRange::new().with_range(10, 19);
+        assert!(content_range.validate_body_len(10).is_ok());
+        for len in &[0, 9, 11, u64::MAX] {
+            let err = content_range.validate_body_len(*len).unwrap_err();
+            assert_eq!(err.status(), 500);
+            assert_eq!(
+                err.downcast_ref::<RangeError>(),
+                Some(&RangeError::BodyLengthMismatch)
+            );
+        }
+
+        let content_range = ByteContentRange::new().with_range(0, u64::MAX - 1);
+        assert!(content_range.validate_body_len(u64::MAX).is_ok());
+
+        let err = ByteContentRange::new().validate_body_len(0).unwrap_err();
+        assert_eq!(err.status(), 500);
+        let err = ByteContentRange::new()
+            .with_range(5, 1)
+            .validate_body_len(0)
+            .unwrap_err();
+        assert_eq!(err.status(), 500);
+    }
+
+    #[test]
+    fn eq_str() {
+        let content_range = ByteContentRange::new().with_range(0, 4).with_size(10);
+        assert_eq!(content_range, "bytes 0-4/10");
+        assert_eq!(content_range, *"bytes 0-4/10");
+        assert_ne!(content_range, "bytes 0-4/*");
+    }
+
+    #[test]
+    fn unsatisfied() -> crate::Result<()> {
+        let content_range = ByteContentRange::unsatisfied(10);
+        assert_eq!(content_range, "bytes */10");
+        assert!(content_range.is_unsatisfied());
+        assert_eq!(ByteContentRange::from_str("bytes */10")?, content_range);
+
+        assert!(!ByteContentRange::new().is_unsatisfied());
+        assert!(!ByteContentRange::new()
+            .with_range(0, 4)
+            .with_size(10)
+            .is_unsatisfied());
+        Ok(())
+    }
+
+    #[test]
+    fn for_range_and_size() -> crate::Result<()> {
+        let content_range = ByteContentRange::for_range_and_size(ByteRange::new(0, 9), 10)?;
+        assert_eq!(content_range, "bytes 0-9/10");
+        assert!(content_range.is_valid());
+
+        for range in &[
+            ByteRange::new(0, 10),
+            ByteRange::new(0, None),
+            ByteRange::new(None, 5),
+        ] {
+            let err = ByteContentRange::for_range_and_size(*range, 10).unwrap_err();
+            assert_eq!(err.status(), 416, "{}", range);
+        }
+        let err = ByteContentRange::for_range_and_size(ByteRange::new(5, 1), 10).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Reversed)
+        );
+        assert!(ByteContentRange::for_range_and_size(ByteRange::new(0, 0), 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn length() -> crate::Result<()> {
+        assert_eq!(
+            ByteContentRange::from_str("bytes 0-4/10")?.length(),
+            Some(5)
+        );
+        assert_eq!(ByteContentRange::from_str("bytes 0-4/*")?.length(), Some(5));
+        assert_eq!(ByteContentRange::from_str("bytes */10")?.length(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn content_length() {
+        let content_range = ByteContentRange::new().with_range(5, 5).with_size(10);
+        assert_eq!(content_range.content_length(), Some(1));
+        let content_range = ByteContentRange::new().with_range(0, u64::MAX);
+        assert_eq!(content_range.content_length(), None);
+        let content_range = ByteContentRange::new().with_range(5, 1);
+        assert_eq!(content_range.content_length(), None);
+    }
+
+    #[test]
+    fn display() {
+        let content_range = ByteContentRange::new().with_range(1, 5).with_size(10);
+        assert_eq!(content_range.to_string(), "bytes 1-5/10");
+
+        let content_range = ByteContentRange::new().with_range(1, 5);
+        assert_eq!(content_range.to_string(), "bytes 1-5/*");
+
+        let content_range = ByteContentRange::new().with_size(10);
+        assert_eq!(content_range.to_string(), "bytes */10");
+    }
+
+    #[test]
+    fn value_is_single_line() {
+        let values = [
+            ByteContentRange::new(),
+            ByteContentRange::new().with_size(0),
+            ByteContentRange::new().with_range(0, u64::MAX),
+            ByteContentRange::new()
+                .with_range(u64::MAX, u64::MAX)
+                .with_size(u64::MAX),
+        ];
+        for content_range in values.iter() {
+            let value = content_range.value();
+            assert!(value
+                .as_str()
+                .bytes()
+                .all(|b| b == b' ' || b.is_ascii_graphic()));
+        }
+    }
+
+    #[test]
+    fn digits_only() {
+        for s in &[
+            "bytes +1-+5/+10",
+            "bytes +1-5/10",
+            "bytes 1-5/+10",
+            "bytes */+10",
+        ] {
+            let err = ByteContentRange::from_str(s).unwrap_err();
+            assert_eq!(err.status(), 400);
+            assert_eq!(
+                err.to_string(),
+                "Invalid Content-Range header: value is not a number"
+            );
+        }
+    }
+
+    #[test]
+    fn separators_and_whitespace() -> crate::Result<()> {
+        let err = ByteContentRange::from_str("bytes 1-5/10/20").unwrap_err();
+        assert_eq!(err.status(), 400);
+        assert_eq!(
+            err.to_string(),
+            "Invalid Content-Range header: too many '/' separators"
+        );
+
+        for s in &[
+            "bytes 1-5 / 10",
+            "bytes 1-5/ 10",
+            "bytes 1 -5/10",
+            "bytes 1-5\t/10",
+        ] {
+            let err = ByteContentRange::from_str(s).unwrap_err();
+            assert_eq!(err.status(), 400);
+            assert_eq!(
+                err.to_string(),
+                "Invalid Content-Range header: unexpected whitespace"
+            );
+        }
+
+        let content_range = ByteContentRange::from_str("  bytes  1-5/10 ")?;
+        assert_eq!(content_range.range(), Some((1, 5)));
+        Ok(())
+    }
+
+    #[test]
+    fn bad_request_on_parse_error() {
+        for s in &["bytes 5-1/10", "bytes 1-5", "bytes a-5/10", "bytes */*"] {
+            let err = ByteContentRange::from_str(s).unwrap_err();
+            assert_eq!(err.status(), 400);
+        }
+
+        let err = ByteContentRange::from_str("bytes */*").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Malformed(
+                "Content-Range requires a range or a complete length"
+            ))
+        );
+    }
+
+    #[test]
+    fn same_status_for_parse_and_build() {
+        let parsed = ByteContentRange::from_str("bytes 1-10/10").unwrap_err();
+        let built = ByteContentRange::new()
+            .with_range(1, 10)
+            .with_size(10)
+            .try_build()
+            .unwrap_err();
+        let converted =
+            ByteContentRange::for_range_and_size(ByteRange::new(1, 10), 10).unwrap_err();
+        for err in &[parsed, built, converted] {
+            assert_eq!(err.status(), 416);
+            assert_eq!(
+                err.downcast_ref::<RangeError>(),
+                Some(&RangeError::Unsatisfiable(
+                    "Content-Range end exceeds the complete length"
+                ))
+            );
+        }
+
+        let parsed = ByteContentRange::from_str("bytes 5-1/10").unwrap_err();
+        let built = ByteContentRange::new()
+            .with_range(5, 1)
+            .with_size(10)
+            .try_build()
+            .unwrap_err();
+        let converted = ByteContentRange::for_range_and_size(ByteRange::new(5, 1), 10).unwrap_err();
+        for err in &[parsed, built, converted] {
+            assert_eq!(err.status(), 400);
+            assert_eq!(
+                err.downcast_ref::<RangeError>(),
+                Some(&RangeError::Reversed)
+            );
+        }
+    }
+}
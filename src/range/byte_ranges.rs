@@ -0,0 +1,2816 @@
+//! HTTP Range request header.
+
+use crate::headers::{HeaderName, HeaderValue, Headers, ToHeaderValues, RANGE};
+use crate::range::{
+    ByteContentRange, RangeError, ResolvedRange, SequentialReader, StrictOpts, Unit,
+};
+use crate::StatusCode;
+
+use futures_lite::{io, prelude::*};
+use serde::de::{Error as DeError, MapAccess, Unexpected, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fmt::{self, Debug, Display, Write};
+use std::iter::{FromIterator, Iterator};
+use std::ops::Range;
+use std::option;
+use std::slice;
+use std::str::FromStr;
+use std::vec;
+
+/// HTTP Range request header.
+///
+/// The `Range` header indicates the parts of a document the server should
+/// return. Only the `bytes` range unit is supported.
+///
+/// With serde, `ByteRanges` serializes to a sequence of [`ByteRange`]s. An
+/// empty sequence is rejected when deserializing, the same as an empty
+/// `Range` header.
+///
+/// The derived `PartialEq` compares ranges structurally: the same ranges,
+/// spelled the same way and in the same order. Use
+/// [`equivalent`](#method.equivalent) to check whether two sets request the
+/// same bytes of a document.
+///
+/// # Specifications
+///
+/// - [RFC 7233, section 3.1: Range](https://tools.ietf.org/html/rfc7233#section-3.1)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::{Method, Request, Url};
+/// use http_types::range::{ByteRange, ByteRanges};
+///
+/// let mut ranges = ByteRanges::new();
+/// ranges.push(0, 499);
+/// ranges.push(None, 500);
+///
+/// let mut req = Request::new(Method::Get, Url::parse("https://example.com")?);
+/// ranges.apply(&mut req);
+///
+/// let ranges = ByteRanges::from_headers(req)?.unwrap();
+/// let mut iter = ranges.iter();
+/// assert_eq!(iter.next(), Some(&ByteRange::new(0, 499)));
+/// assert_eq!(iter.next(), Some(&ByteRange::new(None, 500)));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ByteRanges {
+    ranges: Vec<ByteRange>,
+}
+
+impl ByteRanges {
+    /// Create a new instance with an empty set of ranges.
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// Create a new instance from headers.
+    ///
+    /// Returns `None` if the `Range` header is missing. A header may list
+    /// multiple comma-separated ranges. If multiple `Range` headers are
+    /// found, the ranges of all headers are combined in order.
+    ///
+    /// If the header uses a range unit other than `bytes`, an error wrapping
+    /// [`RangeError::InvalidUnit`] is returned. Servers which do not support
+    /// the unit may choose to ignore the header and serve the full document.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(RANGE) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        let mut ranges = Vec::new();
+        for value in headers.iter() {
+            ranges.extend(Self::from_str(value.as_str())?.ranges);
+        }
+        Ok(Some(Self { ranges }))
+    }
+
+    /// Get the range unit of the `Range` header, whether or not it is
+    /// supported.
+    ///
+    /// Returns `None` if the `Range` header is missing or has no unit. If
+    /// multiple `Range` headers are found the last one is used.
+    ///
+    /// This allows handling custom range units manually, or responding with
+    /// an appropriate status, as [`from_headers`](#method.from_headers)
+    /// only supports the `bytes` unit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::{Method, Request, Url};
+    /// use http_types::range::{ByteRanges, Unit};
+    ///
+    /// let mut req = Request::new(Method::Get, Url::parse("https://example.com").unwrap());
+    /// req.insert_header("Range", "pages=1-5");
+    ///
+    /// assert!(ByteRanges::from_headers(&req).is_err());
+    /// assert_eq!(ByteRanges::unit_from_headers(&req), Some(Unit::other("pages")));
+    /// ```
+    pub fn unit_from_headers(headers: impl AsRef<Headers>) -> Option<Unit> {
+        let value = headers.as_ref().get(RANGE)?.iter().last()?;
+        Self::parse_unit(value.as_str())
+    }
+
+    /// Get the range unit of a `Range` header value, whether or not it is
+    /// supported.
+    ///
+    /// Returns `None` if the value has no `unit=` prefix.
+    pub fn parse_unit(s: &str) -> Option<Unit> {
+        let index = s.find('=')?;
+        match s[..index].trim() {
+            "" => None,
+            unit => Some(Unit::from(unit)),
+        }
+    }
+
+    /// Sets the `Range` header.
+    ///
+    /// An empty set of ranges can not be represented as a valid header, so
+    /// this does nothing if the set is empty.
+    pub fn apply(&self, mut headers: impl AsMut<Headers>) {
+        if let Ok(value) = self.try_value() {
+            headers.as_mut().insert(RANGE, value);
+        }
+    }
+
+    /// Get the `HeaderName`.
+    pub fn name(&self) -> HeaderName {
+        RANGE
+    }
+
+    /// Get the `HeaderValue`.
+    ///
+    /// An empty set of ranges produces the invalid `bytes=` value. Use
+    /// [`try_value`](#method.try_value) to reject empty sets.
+    pub fn value(&self) -> HeaderValue {
+        let s = self.to_string();
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(s.into()) }
+    }
+
+    /// Get the `HeaderValue`, or an error if the set of ranges is empty.
+    pub fn try_value(&self) -> crate::Result<HeaderValue> {
+        if self.ranges.is_empty() {
+            return Err(
+                RangeError::Malformed("Range header requires at least one range")
+                    .with_status(StatusCode::InternalServerError),
+            );
+        }
+        Ok(self.value())
+    }
+
+    /// Returns the range unit of the set.
+    ///
+    /// Only the `bytes` unit is currently supported, so this always returns
+    /// [`Unit::Bytes`]. Use [`unit_from_headers`](#method.unit_from_headers)
+    /// to learn the unit of a `Range` header before parsing it.
+    pub fn unit(&self) -> Unit {
+        Unit::Bytes
+    }
+
+    /// Returns the number of ranges in the set.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Returns `true` if the set contains no ranges.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Returns `true` if the set contains exactly one range.
+    ///
+    /// A single range is served as a `206 Partial Content` response with a
+    /// `Content-Range` header. The predicate looks at the ranges as they were
+    /// requested: call [`coalesce`](#method.coalesce) first so that ranges
+    /// merging into one are served as a single range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let mut ranges: ByteRanges = "bytes=0-49,50-99".parse()?;
+    /// assert!(ranges.is_multi());
+    ///
+    /// ranges.coalesce(1000);
+    /// assert!(ranges.is_single());
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn is_single(&self) -> bool {
+        self.ranges.len() == 1
+    }
+
+    /// Returns `true` if the set contains more than one range.
+    ///
+    /// Multiple ranges are served as a `multipart/byteranges` body. See
+    /// [`is_single`](#method.is_single) for how this interacts with
+    /// [`coalesce`](#method.coalesce).
+    pub fn is_multi(&self) -> bool {
+        self.ranges.len() > 1
+    }
+
+    /// Push a range into the set of ranges.
+    pub fn push(&mut self, start: impl Into<Option<u64>>, end: impl Into<Option<u64>>) {
+        self.ranges.push(ByteRange::new(start, end));
+    }
+
+    /// Push a `ByteRange` into the set of ranges.
+    pub fn push_range(&mut self, range: ByteRange) {
+        self.ranges.push(range);
+    }
+
+    /// Push a range into the set of ranges, ensuring the set stays strictly
+    /// ascending and non-overlapping.
+    ///
+    /// The document size is required to resolve suffix and open-ended ranges
+    /// into concrete bounds. Returns a `416 Requested Range Not Satisfiable`
+    /// error if the range is not satisfiable, and a [`RangeError::Unordered`]
+    /// error with a `500 Internal Server Error` status if it overlaps or
+    /// precedes the previous range of the set. Use [`push`](#method.push) to
+    /// push a range without validation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let mut ranges = ByteRanges::new();
+    /// ranges.try_push(0, 99, 1000)?;
+    /// ranges.try_push(None, 100, 1000)?;
+    /// assert!(ranges.try_push(50, 149, 1000).is_err());
+    /// assert_eq!(ranges.to_string(), "bytes=0-99,-100");
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn try_push(
+        &mut self,
+        start: impl Into<Option<u64>>,
+        end: impl Into<Option<u64>>,
+        size: u64,
+    ) -> crate::Result<()> {
+        let range = ByteRange::new(start, end);
+        let (start, _) = range.try_resolve(size)?;
+        if let Some((_, prev_end)) = self.ranges.last().and_then(|r| r.resolve(size)) {
+            if start <= prev_end {
+                return Err(RangeError::Unordered.into_error());
+            }
+        }
+        self.ranges.push(range);
+        Ok(())
+    }
+
+    /// Returns the first range of the set, if any.
+    pub fn first(&self) -> Option<ByteRange> {
+        self.ranges.first().copied()
+    }
+
+    /// Returns the ranges as a slice.
+    pub fn as_slice(&self) -> &[ByteRange] {
+        &self.ranges
+    }
+
+    /// An iterator visiting all ranges.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.ranges.iter(),
+        }
+    }
+
+    /// Returns `true` if all ranges are satisfiable for a document of the
+    /// given size.
+    pub fn match_size(&self, size: u64) -> bool {
+        self.ranges.iter().all(|range| range.match_size(size))
+    }
+
+    /// Returns `true` if any range covers the given offset, for a document of
+    /// the given size.
+    ///
+    /// Unsatisfiable ranges cover no offset. See [`ByteRange::contains`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let ranges: ByteRanges = "bytes=0-4,-5".parse()?;
+    /// assert!(ranges.contains_offset(2, 100));
+    /// assert!(ranges.contains_offset(97, 100));
+    /// assert!(!ranges.contains_offset(50, 100));
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn contains_offset(&self, offset: u64, size: u64) -> bool {
+        self.ranges.iter().any(|range| range.contains(offset, size))
+    }
+
+    /// Resolve all ranges into concrete, inclusive `(start, end)` bounds for a
+    /// document of the given size.
+    ///
+    /// Returns a `416 Requested Range Not Satisfiable` error if any of the
+    /// ranges can not be satisfied.
+    pub fn resolve(&self, size: u64) -> crate::Result<Vec<(u64, u64)>> {
+        self.iter_resolved(size).collect()
+    }
+
+    /// An iterator lazily resolving each range into concrete, inclusive
+    /// `(start, end)` bounds for a document of the given size.
+    ///
+    /// Unsatisfiable ranges yield a `416 Requested Range Not Satisfiable`
+    /// error in place of their bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let ranges: ByteRanges = "bytes=0-4,-5,20-".parse()?;
+    /// let mut iter = ranges.iter_resolved(10);
+    /// assert_eq!(iter.next().unwrap()?, (0, 4));
+    /// assert_eq!(iter.next().unwrap()?, (5, 9));
+    /// assert!(iter.next().unwrap().is_err());
+    /// assert!(iter.next().is_none());
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn iter_resolved(&self, size: u64) -> impl Iterator<Item = crate::Result<(u64, u64)>> + '_ {
+        self.ranges.iter().map(move |range| range.try_resolve(size))
+    }
+
+    /// Read the ranges from a forward-only source, for a document of the given
+    /// size.
+    ///
+    /// The ranges must be sorted and must not overlap, as produced by
+    /// [`coalesce`](#method.coalesce); otherwise a `500 Internal Server
+    /// Error` is returned. A `416 Requested Range Not Satisfiable` error is
+    /// returned if any of the ranges can not be satisfied. See
+    /// [`SequentialReader`] for an example.
+    pub fn sequential_reader<R>(&self, reader: R, size: u64) -> crate::Result<SequentialReader<R>>
+    where
+        R: AsyncRead + Unpin,
+    {
+        SequentialReader::new(self, reader, size)
+    }
+
+    /// Resolve each satisfiable range into the `Content-Range` of a
+    /// `multipart/byteranges` body part, for a document of the given size.
+    ///
+    /// Unsatisfiable ranges are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let ranges: ByteRanges = "bytes=0-4,-5,20-".parse()?;
+    /// let content_ranges = ranges.to_content_ranges(10);
+    /// assert_eq!(content_ranges.len(), 2);
+    /// assert_eq!(content_ranges[1].to_string(), "bytes 5-9/10");
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn to_content_ranges(&self, size: u64) -> Vec<ByteContentRange> {
+        self.ranges
+            .iter()
+            .filter_map(|range| range.to_content_range(size).ok())
+            .collect()
+    }
+
+    /// Resolve each range into the `Content-Range` and header block of a
+    /// `multipart/byteranges` body part, for a document of the given size and
+    /// content type.
+    ///
+    /// Parts are returned in the order of the ranges. Returns a `416 Requested
+    /// Range Not Satisfiable` error if any of the ranges is not satisfiable,
+    /// and a `500 Internal Server Error` if the content type is invalid. See
+    /// [`ByteContentRange::part_headers`] for the header block format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let ranges: ByteRanges = "bytes=0-4,-5".parse()?;
+    /// let parts = ranges.parts(10, "text/plain")?;
+    ///
+    /// assert_eq!(parts.len(), 2);
+    /// assert_eq!(parts[1].0.range(), Some((5, 9)));
+    /// assert_eq!(
+    ///     parts[1].1,
+    ///     "Content-Type: text/plain\r\nContent-Range: bytes 5-9/10\r\n\r\n"
+    /// );
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn parts(
+        &self,
+        size: u64,
+        content_type: &str,
+    ) -> crate::Result<Vec<(ByteContentRange, String)>> {
+        self.iter_resolved(size)
+            .map(|bounds| {
+                let (start, end) = bounds?;
+                let content_range = ByteContentRange::new()
+                    .with_range(start, end)
+                    .with_size(size);
+                let headers = content_range.part_headers(content_type)?;
+                Ok((content_range, headers))
+            })
+            .collect()
+    }
+
+    /// Rewrite the ranges into concrete pieces aligned to `chunk`-sized
+    /// blocks, for a document of the given size.
+    ///
+    /// Each range is resolved and split on every multiple of `chunk`, so the
+    /// first and last pieces of a range may be shorter than `chunk`.
+    /// Unsatisfiable ranges are skipped, and an empty set is returned if
+    /// `chunk` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let ranges: ByteRanges = "bytes=3-17".parse()?;
+    /// assert_eq!(ranges.chunked(100, 8).to_string(), "bytes=3-7,8-15,16-17");
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn chunked(&self, size: u64, chunk: u64) -> ByteRanges {
+        let mut chunked = ByteRanges::new();
+        if chunk == 0 {
+            return chunked;
+        }
+
+        for (start, end) in self.ranges.iter().filter_map(|range| range.resolve(size)) {
+            let mut piece_start = start;
+            loop {
+                let block_end = (piece_start / chunk)
+                    .checked_add(1)
+                    .and_then(|n| n.checked_mul(chunk))
+                    .map_or(u64::MAX, |n| n - 1);
+                let piece_end = block_end.min(end);
+                chunked.push(piece_start, piece_end);
+                if piece_end == end {
+                    break;
+                }
+                piece_start = piece_end + 1;
+            }
+        }
+        chunked
+    }
+
+    /// Remove the ranges which are not satisfiable for a document of the
+    /// given size, keeping the order of the remaining ranges.
+    pub fn retain_satisfiable(&mut self, size: u64) {
+        self.ranges.retain(|range| range.match_size(size));
+    }
+
+    /// Keep only the first `max` ranges of the set.
+    ///
+    /// When serving a request, the recommended order is to first
+    /// [`retain_satisfiable`](#method.retain_satisfiable), then
+    /// [`coalesce`](#method.coalesce), and finally `limit`. This serves the
+    /// first `max` satisfiable ranges, and avoids counting ranges which would
+    /// be merged together against the limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let mut ranges: ByteRanges = "bytes=500-,0-9,5-19,40-49,60-69".parse()?;
+    /// ranges.retain_satisfiable(100);
+    /// ranges.coalesce(100);
+    /// ranges.limit(2);
+    /// assert_eq!(ranges.to_string(), "bytes=0-19,40-49");
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn limit(&mut self, max: usize) {
+        self.ranges.truncate(max);
+    }
+
+    /// Remove exact duplicate ranges, keeping the first occurrence of each.
+    ///
+    /// Unlike [`coalesce`](#method.coalesce) this does not need the document
+    /// size, and neither merges nor reorders ranges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let mut ranges: ByteRanges = "bytes=0-4,10-,0-4".parse()?;
+    /// ranges.dedup();
+    /// assert_eq!(ranges.to_string(), "bytes=0-4,10-");
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn dedup(&mut self) {
+        let mut seen = HashSet::with_capacity(self.ranges.len());
+        self.ranges.retain(|range| seen.insert(*range));
+    }
+
+    /// Merge overlapping and adjacent ranges, for a document of the given
+    /// size.
+    ///
+    /// Ranges are resolved into concrete bounds and sorted by their start.
+    /// Unsatisfiable ranges are removed.
+    ///
+    /// The ranges are sorted once and merged in a single pass, so coalescing
+    /// `n` ranges takes `O(n log n)` time however they overlap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let mut ranges: ByteRanges = "bytes=50-99,0-9,5-19,-10,200-".parse()?;
+    /// ranges.coalesce(100);
+    /// assert_eq!(ranges.to_string(), "bytes=0-19,50-99");
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn coalesce(&mut self, size: u64) {
+        self.ranges = self
+            .coverage(size)
+            .into_iter()
+            .map(|(start, end)| ByteRange::new(start, end))
+            .collect();
+    }
+
+    /// Returns the sorted, non-overlapping `(start, end)` intervals covered by
+    /// the ranges, for a document of the given size.
+    ///
+    /// These are the bounds of the [`coalesce`](#method.coalesce)d ranges,
+    /// computed in `O(n log n)` time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let ranges: ByteRanges = "bytes=50-59,0-9,5-19".parse()?;
+    /// assert_eq!(ranges.coverage(100), vec![(0, 19), (50, 59)]);
+    /// assert_eq!(ranges.gaps(100), vec![(20, 49), (60, 99)]);
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn coverage(&self, size: u64) -> Vec<(u64, u64)> {
+        let mut bounds: Vec<(u64, u64)> = self
+            .ranges
+            .iter()
+            .filter_map(|range| range.resolve(size))
+            .collect();
+        bounds.sort_unstable();
+
+        let mut merged: Vec<(u64, u64)> = Vec::with_capacity(bounds.len());
+        for (start, end) in bounds {
+            match merged.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        merged
+    }
+
+    /// Returns the sum of the lengths of all ranges, for a document of the
+    /// given size.
+    ///
+    /// Unlike [`coverage`](#method.coverage), overlapping ranges are counted
+    /// once per occurrence. Comparing both reveals clients requesting the
+    /// same bytes many times. Unsatisfiable ranges are skipped, and `None` is
+    /// returned if the sum overflows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let ranges: ByteRanges = "bytes=0-9,0-9,5-14".parse()?;
+    /// assert_eq!(ranges.requested_len(100), Some(30));
+    ///
+    /// let covered: u64 = ranges.coverage(100).iter().map(|(s, e)| e - s + 1).sum();
+    /// assert_eq!(covered, 15);
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn requested_len(&self, size: u64) -> Option<u64> {
+        self.ranges
+            .iter()
+            .filter_map(|range| range.len(size))
+            .try_fold(0u64, |total, len| total.checked_add(len))
+    }
+
+    /// Add a range to the set and [`coalesce`](#method.coalesce) it, for a
+    /// document of the given size.
+    ///
+    /// This keeps the set as the minimal list of covered intervals, which
+    /// allows using it to track the progress of a download.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::{ByteRange, ByteRanges};
+    ///
+    /// let mut downloaded = ByteRanges::new();
+    /// downloaded.insert_covered(ByteRange::new(0, 499), 1000);
+    /// downloaded.insert_covered(ByteRange::new(None, 250), 1000);
+    /// assert_eq!(downloaded.gaps(1000), vec![(500, 749)]);
+    ///
+    /// downloaded.insert_covered(ByteRange::new(500, 749), 1000);
+    /// assert_eq!(downloaded.to_string(), "bytes=0-999");
+    /// assert!(downloaded.gaps(1000).is_empty());
+    /// ```
+    pub fn insert_covered(&mut self, range: ByteRange, size: u64) {
+        self.ranges.push(range);
+        self.coalesce(size);
+    }
+
+    /// Returns the sorted `(start, end)` intervals of a document of the given
+    /// size which are not covered by the ranges.
+    ///
+    /// This is the complement of [`coverage`](#method.coverage).
+    pub fn gaps(&self, size: u64) -> Vec<(u64, u64)> {
+        let mut gaps = Vec::new();
+        let mut next = 0;
+        for (start, end) in self.coverage(size) {
+            if start > next {
+                gaps.push((next, start - 1));
+            }
+            next = end + 1;
+        }
+        if next < size {
+            gaps.push((next, size - 1));
+        }
+        gaps
+    }
+
+    /// Returns a coalesced copy of the ranges, for a document of the given
+    /// size.
+    ///
+    /// The `Display` output of the returned set only depends on which bytes
+    /// are requested, not on how they were spelled or ordered, which makes
+    /// it suitable as a cache key. See [`coalesce`](#method.coalesce).
+    pub fn canonical(&self, size: u64) -> ByteRanges {
+        let mut canonical = self.clone();
+        canonical.coalesce(size);
+        canonical
+    }
+
+    /// Returns the comma-separated list of ranges, without the `bytes=` unit
+    /// prefix used by `Display`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let ranges: ByteRanges = "bytes=0-4,10-14".parse()?;
+    /// assert_eq!(ranges.to_range_list_string(), "0-4,10-14");
+    /// assert_eq!(ranges.to_string(), "bytes=0-4,10-14");
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn to_range_list_string(&self) -> String {
+        let mut s = String::new();
+        // Writing into a `String` never fails.
+        self.write_range_list(&mut s).unwrap();
+        s
+    }
+
+    fn write_range_list(&self, w: &mut impl Write) -> fmt::Result {
+        for (n, range) in self.ranges.iter().enumerate() {
+            if n > 0 {
+                w.write_char(',')?;
+            }
+            write!(w, "{}", range)?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if both sets request the same bytes of a document of
+    /// the given size, regardless of order, spelling, or overlaps.
+    ///
+    /// This compares the [`canonical`](#method.canonical) forms of the sets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let a: ByteRanges = "bytes=0-4,10-14".parse()?;
+    /// let b: ByteRanges = "bytes=10-14,0-4".parse()?;
+    /// assert_ne!(a, b);
+    /// assert!(a.equivalent(&b, 100));
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn equivalent(&self, other: &ByteRanges, size: u64) -> bool {
+        self.canonical(size) == other.canonical(size)
+    }
+
+    /// Parse a `Range` header value, tolerating a single leading `+` sign on
+    /// each range bound.
+    ///
+    /// Strict parsing through `FromStr` rejects signed bounds. See
+    /// [`ByteRange::from_str_lenient`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// assert!("bytes=+0-+5".parse::<ByteRanges>().is_err());
+    ///
+    /// let ranges = ByteRanges::from_str_lenient("bytes=+0-+5")?;
+    /// assert_eq!(ranges.to_string(), "bytes=0-5");
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn from_str_lenient(s: &str) -> crate::Result<Self> {
+        Self::parse(s, true)
+    }
+
+    /// Parse a `Range` header value, rejecting suffix ranges.
+    ///
+    /// Some backends can only serve ranges with a known start, as suffix
+    /// ranges require knowing the document size up front. This returns a
+    /// `400 Bad Request` error if any of the ranges is a suffix range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteRanges;
+    ///
+    /// assert!(ByteRanges::from_str_disallowing_suffix("bytes=0-4,10-").is_ok());
+    ///
+    /// let err = ByteRanges::from_str_disallowing_suffix("bytes=0-4,-5").unwrap_err();
+    /// assert_eq!(err.status(), 400);
+    /// ```
+    pub fn from_str_disallowing_suffix(s: &str) -> crate::Result<Self> {
+        let ranges = Self::parse(s, false)?;
+        if ranges.iter().any(|range| range.start.is_none()) {
+            return Err(RangeError::Malformed(
+                "Invalid Range header: suffix ranges are not supported",
+            )
+            .into_error());
+        }
+        Ok(ranges)
+    }
+
+    /// Parse a `Range` header value, and check it against a document of the
+    /// given size.
+    ///
+    /// This returns a `416 Requested Range Not Satisfiable` error if any of
+    /// the ranges can not be satisfied, and a `400 Bad Request` error if the
+    /// ranges request more than `max_total` bytes in total, overlaps
+    /// included. See [`requested_len`](#method.requested_len).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRanges;
+    ///
+    /// let ranges = ByteRanges::from_str_bounded("bytes=0-49,-50", 100, 200)?;
+    /// assert_eq!(ranges.len(), 2);
+    ///
+    /// let err = ByteRanges::from_str_bounded("bytes=0-,0-,0-", 100, 200).unwrap_err();
+    /// assert_eq!(err.status(), 400);
+    ///
+    /// let err = ByteRanges::from_str_bounded("bytes=100-", 100, 200).unwrap_err();
+    /// assert_eq!(err.status(), 416);
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn from_str_bounded(s: &str, size: u64, max_total: u64) -> crate::Result<Self> {
+        let ranges = Self::parse(s, false)?;
+        ranges.resolve(size)?;
+        match ranges.requested_len(size) {
+            Some(total) if total <= max_total => Ok(ranges),
+            _ => Err(RangeError::TooLarge.into_error()),
+        }
+    }
+
+    /// Parse a `Range` header value into a clean set of ranges for a document
+    /// of the given size, or reject it.
+    ///
+    /// The ranges are checked against `opts`, then returned
+    /// [`coalesced`](#method.coalesce): resolved, sorted and merged.
+    ///
+    /// - Too many ranges return an error wrapping
+    ///   [`RangeError::TooManyRanges`], and too many bytes in total an error
+    ///   wrapping [`RangeError::TooLarge`].
+    /// - Disallowed suffix and overlapping ranges are malformed.
+    /// - Ranges which can not be satisfied return a
+    ///   `416 Requested Range Not Satisfiable` error.
+    ///
+    /// All other errors use `400 Bad Request`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::{ByteRanges, StrictOpts};
+    ///
+    /// let opts = StrictOpts::new().with_max_count(2).allow_overlap(false);
+    ///
+    /// let ranges = ByteRanges::parse_strict("bytes=50-,-60", 100, opts);
+    /// assert!(ranges.is_err());
+    ///
+    /// let ranges = ByteRanges::parse_strict("bytes=50-,0-9", 100, opts)?;
+    /// assert_eq!(ranges.to_string(), "bytes=0-9,50-99");
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn parse_strict(s: &str, size: u64, opts: StrictOpts) -> crate::Result<Self> {
+        let mut ranges = if opts.allow_suffix {
+            Self::parse(s, false)?
+        } else {
+            Self::from_str_disallowing_suffix(s)?
+        };
+
+        if let Some(max) = opts.max_count {
+            if ranges.len() > max {
+                return Err(RangeError::TooManyRanges.into_error());
+            }
+        }
+
+        let mut bounds = ranges.resolve(size)?;
+        if let Some(max) = opts.max_total_len {
+            match ranges.requested_len(size) {
+                Some(total) if total <= max => {}
+                _ => return Err(RangeError::TooLarge.into_error()),
+            }
+        }
+
+        if !opts.allow_overlap {
+            bounds.sort_unstable();
+            if bounds.windows(2).any(|pair| pair[0].1 >= pair[1].0) {
+                return Err(
+                    RangeError::Malformed("Invalid Range header: ranges overlap").into_error(),
+                );
+            }
+        }
+
+        ranges.coalesce(size);
+        Ok(ranges)
+    }
+
+    fn parse(s: &str, lenient: bool) -> crate::Result<Self> {
+        // Range units are case-insensitive.
+        let s = trim_ows(s);
+        let s = match s.get(..6) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("bytes=") => &s[6..],
+            _ => return Err(RangeError::InvalidUnit.into_error()),
+        };
+
+        // Optional whitespace is allowed around the elements of the list.
+        let ranges = s
+            .split(',')
+            .map(|part| match trim_ows(part) {
+                "" => Err(RangeError::Malformed(
+                    "Invalid Range header: range list has an empty element",
+                )
+                .into_error()),
+                part => ByteRange::parse(part, lenient),
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok(Self { ranges })
+    }
+}
+
+impl FromStr for ByteRanges {
+    type Err = crate::Error;
+
+    /// Parse a `bytes=` prefixed, comma-separated list of ranges.
+    ///
+    /// Empty entries, such as the ones produced by leading, trailing or
+    /// doubled commas, are rejected as malformed with a `400 Bad Request`
+    /// status.
+    fn from_str(s: &str) -> crate::Result<Self> {
+        Self::parse(s, false)
+    }
+}
+
+impl Display for ByteRanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("bytes=")?;
+        self.write_range_list(f)
+    }
+}
+
+impl PartialEq<str> for ByteRanges {
+    fn eq(&self, other: &str) -> bool {
+        self.to_string().as_str() == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for ByteRanges {
+    fn eq(&self, other: &&'a str) -> bool {
+        self == *other
+    }
+}
+
+impl ToHeaderValues for ByteRanges {
+    type Iter = option::IntoIter<HeaderValue>;
+    fn to_header_values(&self) -> crate::Result<Self::Iter> {
+        self.try_value()?.to_header_values()
+    }
+}
+
+impl IntoIterator for ByteRanges {
+    type Item = ByteRange;
+    type IntoIter = IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.ranges.into_iter(),
+        }
+    }
+}
+
+impl Serialize for ByteRanges {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(&self.ranges)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteRanges {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let ranges = Vec::<ByteRange>::deserialize(deserializer)?;
+        if ranges.is_empty() {
+            return Err(DeError::invalid_length(0, &"at least one range"));
+        }
+        Ok(Self { ranges })
+    }
+}
+
+impl From<ByteRange> for ByteRanges {
+    /// Create a set containing a single range.
+    fn from(range: ByteRange) -> Self {
+        Self {
+            ranges: vec![range],
+        }
+    }
+}
+
+impl FromIterator<ByteRange> for ByteRanges {
+    /// Collect ranges into a set, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::{ByteRange, ByteRanges};
+    ///
+    /// let ranges: ByteRanges = vec![ByteRange::new(0, 4), ByteRange::new(None, 5)]
+    ///     .into_iter()
+    ///     .collect();
+    /// assert_eq!(ranges.to_string(), "bytes=0-4,-5");
+    /// ```
+    fn from_iter<I: IntoIterator<Item = ByteRange>>(iter: I) -> Self {
+        Self {
+            ranges: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<ByteRange> for ByteRanges {
+    /// Append ranges to the set, in order.
+    fn extend<I: IntoIterator<Item = ByteRange>>(&mut self, iter: I) {
+        self.ranges.extend(iter);
+    }
+}
+
+impl FromIterator<Range<u64>> for ByteRanges {
+    /// Collect half-open std ranges into a set of ranges.
+    ///
+    /// Each range is converted through the `TryFrom<Range<u64>>`
+    /// implementation of [`ByteRange`]. Empty ranges target no bytes, and are
+    /// skipped, so an input made only of empty ranges yields an empty set,
+    /// which can not be serialized into a header.
+    ///
+    /// To report empty ranges instead, convert each range with `try_from` and
+    /// collect into a `Result`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::{ByteRange, ByteRanges};
+    /// use std::convert::TryFrom;
+    ///
+    /// let ranges: ByteRanges = vec![0..500, 700..700, 1000..2000].into_iter().collect();
+    /// assert_eq!(ranges.to_string(), "bytes=0-499,1000-1999");
+    ///
+    /// let ranges: http_types::Result<ByteRanges> = vec![0..500, 700..700]
+    ///     .into_iter()
+    ///     .map(ByteRange::try_from)
+    ///     .collect();
+    /// assert!(ranges.is_err());
+    /// ```
+    fn from_iter<I: IntoIterator<Item = Range<u64>>>(iter: I) -> Self {
+        Self {
+            ranges: iter
+                .into_iter()
+                .filter_map(|range| ByteRange::try_from(range).ok())
+                .collect(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a ByteRanges {
+    type Item = &'a ByteRange;
+    type IntoIter = Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An owning iterator over ranges in `ByteRanges`.
+#[derive(Debug)]
+pub struct IntoIter {
+    inner: vec::IntoIter<ByteRange>,
+}
+
+impl Iterator for IntoIter {
+    type Item = ByteRange;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A borrowing iterator over ranges in `ByteRanges`.
+#[derive(Debug)]
+pub struct Iter<'a> {
+    inner: slice::Iter<'a, ByteRange>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a ByteRange;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A single range of bytes.
+///
+/// A range is either bounded (`start-end`), open-ended (`start-`), or a
+/// suffix range (`-length`) targeting the last `length` bytes of a document.
+/// Both bounds are inclusive.
+///
+/// A suffix range longer than the document targets the whole document, as
+/// recommended by RFC 7233. A suffix range of length zero is never
+/// satisfiable.
+///
+/// A `ByteRange` formats to the bare `start-end` form. The `bytes=` unit
+/// prefix of the `Range` header is added by [`ByteRanges`].
+///
+/// With serde, a `ByteRange` serializes to its bare `start-end` string. It
+/// deserializes from either that string or a `{ "start": .., "end": .. }`
+/// map, where missing bounds are open.
+///
+/// # Examples
+///
+/// ```
+/// use http_types::range::{ByteRange, ByteRanges};
+///
+/// let range = ByteRange::new(0, 4);
+/// assert_eq!(range.to_header_string(), "0-4");
+///
+/// let mut ranges = ByteRanges::new();
+/// ranges.push(0, 4);
+/// assert_eq!(ranges.to_string(), "bytes=0-4");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ByteRange {
+    /// The first byte of the range, or `None` for a suffix range.
+    pub start: Option<u64>,
+    /// The last byte of the range, the length of a suffix range, or `None`
+    /// for an open-ended range.
+    pub end: Option<u64>,
+}
+
+impl ByteRange {
+    /// Create a new instance.
+    ///
+    /// The bounds are not validated, so this may create ranges the parser
+    /// would reject, such as a reversed range or a range with no bounds at
+    /// all. Such ranges are never satisfiable. Use
+    /// [`try_new`](#method.try_new) to validate the bounds.
+    pub fn new(start: impl Into<Option<u64>>, end: impl Into<Option<u64>>) -> Self {
+        Self {
+            start: start.into(),
+            end: end.into(),
+        }
+    }
+
+    /// Create a new instance, validating the bounds the same way the parser
+    /// does.
+    ///
+    /// Returns an error if the range has no bounds, or if the start is greater
+    /// than the end. The zero-length suffix range `-0` is valid syntax, but it
+    /// can never be satisfied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::{ByteRange, RangeError};
+    ///
+    /// assert!(ByteRange::try_new(1, 5).is_ok());
+    ///
+    /// let err = ByteRange::try_new(5, 1).unwrap_err();
+    /// assert_eq!(err.downcast_ref::<RangeError>(), Some(&RangeError::Reversed));
+    /// ```
+    pub fn try_new(
+        start: impl Into<Option<u64>>,
+        end: impl Into<Option<u64>>,
+    ) -> crate::Result<Self> {
+        match (start.into(), end.into()) {
+            (None, None) => {
+                Err(RangeError::Malformed("Invalid Range header: range has no bounds").into_error())
+            }
+            (Some(start), Some(end)) if start > end => Err(RangeError::Reversed.into_error()),
+            (start, end) => Ok(Self { start, end }),
+        }
+    }
+
+    /// Create an open-ended range resuming a download after the given number
+    /// of bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteRange;
+    ///
+    /// // The connection dropped after 1500 bytes were downloaded.
+    /// let range = ByteRange::resume_from(1500);
+    /// assert_eq!(range.to_header_string(), "1500-");
+    ///
+    /// // The total size is known from a previous `Content-Range` header.
+    /// assert_eq!(ByteRange::resume_bounded(1500, 4000), Some(ByteRange::new(1500, 3999)));
+    /// assert_eq!(ByteRange::resume_bounded(4000, 4000), None);
+    /// ```
+    pub fn resume_from(downloaded: u64) -> Self {
+        Self::new(downloaded, None)
+    }
+
+    /// Create a bounded range resuming a download of a document of the given
+    /// size after the given number of bytes.
+    ///
+    /// Returns `None` if the download is already complete.
+    pub fn resume_bounded(downloaded: u64, size: u64) -> Option<Self> {
+        if downloaded >= size {
+            return None;
+        }
+        Some(Self::new(downloaded, size - 1))
+    }
+
+    /// Get the `HeaderValue`.
+    ///
+    /// This is the bare `start-end` form, without the `bytes=` unit prefix
+    /// used by the `Range` header.
+    pub fn value(&self) -> HeaderValue {
+        let s = self.to_string();
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(s.into()) }
+    }
+
+    /// Returns the bare `start-end` form of the range.
+    ///
+    /// This is the same as the `Display` output, and does not include the
+    /// `bytes=` unit prefix. Use [`ByteRanges`] to produce a complete `Range`
+    /// header value.
+    pub fn to_header_string(&self) -> String {
+        self.to_string()
+    }
+
+    /// Returns `true` if the range is satisfiable for a document of the given
+    /// size.
+    ///
+    /// See [`check_size`](#method.check_size) to learn why a range is not
+    /// satisfiable.
+    pub fn match_size(&self, size: u64) -> bool {
+        self.check_size(size).is_ok()
+    }
+
+    /// Check whether the range is satisfiable for a document of the given
+    /// size.
+    ///
+    /// Returns a `416 Requested Range Not Satisfiable` error describing which
+    /// bound could not be satisfied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteRange;
+    ///
+    /// let err = ByteRange::new(20, None).check_size(10).unwrap_err();
+    /// assert_eq!(err.status(), 416);
+    /// assert_eq!(err.to_string(), "Range not satisfiable: range start exceeds document size");
+    /// ```
+    pub fn check_size(&self, size: u64) -> crate::Result<()> {
+        self.try_resolve(size).map(|_| ())
+    }
+
+    /// Resolve the range into concrete, inclusive `(start, end)` bounds for a
+    /// document of the given size.
+    ///
+    /// Returns `None` if the range is not satisfiable. Per [RFC 7233, section
+    /// 2.1](https://tools.ietf.org/html/rfc7233#section-2.1), a last byte
+    /// position past the end of the document is clamped to the last byte, and
+    /// a suffix range longer than the document resolves to the entire
+    /// document.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteRange;
+    ///
+    /// assert_eq!(ByteRange::new(None, 5).resolve(100), Some((95, 99)));
+    /// assert_eq!(ByteRange::new(0, 500).resolve(100), Some((0, 99)));
+    /// assert_eq!(ByteRange::new(None, 500).resolve(100), Some((0, 99)));
+    /// ```
+    pub fn resolve(&self, size: u64) -> Option<(u64, u64)> {
+        self.try_resolve(size).ok()
+    }
+
+    /// Returns the number of bytes targeted by the range, for a document of
+    /// the given size.
+    ///
+    /// Returns `None` if the range is not satisfiable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteRange;
+    ///
+    /// assert_eq!(ByteRange::new(0, 499).len(1000), Some(500));
+    /// assert_eq!(ByteRange::new(None, 500).len(100), Some(100));
+    /// assert_eq!(ByteRange::new(0, u64::MAX).len(100), Some(100));
+    /// ```
+    pub fn len(&self, size: u64) -> Option<u64> {
+        let (start, end) = self.resolve(size)?;
+        end.checked_sub(start)?.checked_add(1)
+    }
+
+    /// Returns `true` if the range covers the given offset, for a document of
+    /// the given size.
+    ///
+    /// Always returns `false` if the range is not satisfiable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteRange;
+    ///
+    /// assert!(ByteRange::new(None, 5).contains(95, 100));
+    /// assert!(!ByteRange::new(None, 5).contains(94, 100));
+    /// assert!(!ByteRange::new(0, None).contains(100, 100));
+    /// ```
+    pub fn contains(&self, offset: u64, size: u64) -> bool {
+        match self.resolve(size) {
+            Some((start, end)) => start <= offset && offset <= end,
+            None => false,
+        }
+    }
+
+    /// Returns `true` if the range covers the whole document of the given
+    /// size.
+    ///
+    /// Such a range can be answered with a full `200 OK` response instead of
+    /// a partial one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteRange;
+    ///
+    /// assert!(ByteRange::new(0, None).is_full(100));
+    /// assert!(ByteRange::new(0, 99).is_full(100));
+    /// assert!(ByteRange::new(None, 100).is_full(100));
+    /// assert!(!ByteRange::new(1, None).is_full(100));
+    /// ```
+    pub fn is_full(&self, size: u64) -> bool {
+        self.resolve(size) == Some((0, size.saturating_sub(1)))
+    }
+
+    /// Resolve the range into a half-open std range, for a document of the
+    /// given size.
+    ///
+    /// Returns `None` if the range is not satisfiable, or if its end can not
+    /// be represented as an exclusive bound.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteRange;
+    ///
+    /// let body = b"hello world";
+    /// let range = ByteRange::new(None, 5).to_std_range(body.len() as u64).unwrap();
+    /// assert_eq!(range, 6..11);
+    /// assert_eq!(&body[range.start as usize..range.end as usize], b"world");
+    /// ```
+    pub fn to_std_range(&self, size: u64) -> Option<Range<u64>> {
+        let (start, end) = self.resolve(size)?;
+        Some(start..end.checked_add(1)?)
+    }
+
+    /// Resolve the range into the `Content-Range` of a response for a document
+    /// of the given size.
+    ///
+    /// Returns a `416 Requested Range Not Satisfiable` error if the range is
+    /// not satisfiable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRange;
+    ///
+    /// let content_range = ByteRange::new(None, 500).to_content_range(1000)?;
+    /// assert_eq!(content_range.to_string(), "bytes 500-999/1000");
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn to_content_range(&self, size: u64) -> crate::Result<ByteContentRange> {
+        let (start, end) = self.try_resolve(size)?;
+        Ok(ByteContentRange::new()
+            .with_range(start, end)
+            .with_size(size))
+    }
+
+    /// Resolve the range against a document of the given size, keeping track
+    /// of the range as it was requested.
+    ///
+    /// Returns `None` if the range is not satisfiable. See
+    /// [`resolve`](#method.resolve).
+    pub fn resolve_tracked(&self, size: u64) -> Option<ResolvedRange> {
+        let (start, end) = self.resolve(size)?;
+        Some(ResolvedRange::new(*self, start, end))
+    }
+
+    fn try_resolve(&self, size: u64) -> crate::Result<(u64, u64)> {
+        let unsatisfiable = |reason| Err(RangeError::Unsatisfiable(reason).into_error());
+        if size == 0 {
+            return unsatisfiable("Range not satisfiable: document is empty");
+        }
+        let last = size - 1;
+        match (self.start, self.end) {
+            (Some(start), _) if start > last => {
+                unsatisfiable("Range not satisfiable: range start exceeds document size")
+            }
+            (Some(start), Some(end)) if start > end => {
+                unsatisfiable("Range not satisfiable: range start exceeds range end")
+            }
+            (Some(start), Some(end)) => Ok((start, end.min(last))),
+            (Some(start), None) => Ok((start, last)),
+            (None, Some(0)) => unsatisfiable("Range not satisfiable: suffix length is zero"),
+            (None, Some(suffix)) => Ok((size.saturating_sub(suffix), last)),
+            (None, None) => unsatisfiable("Range not satisfiable: range has no bounds"),
+        }
+    }
+
+    /// Seek a reader to the start of the range and limit it to the range's
+    /// length, for a document of the given size.
+    ///
+    /// The returned reader yields exactly the bytes targeted by the range.
+    /// Returns a `416 Requested Range Not Satisfiable` error if the range is
+    /// not satisfiable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> { async_std::task::block_on(async {
+    /// use futures_lite::io::{AsyncReadExt, Cursor};
+    /// use http_types::range::ByteRange;
+    ///
+    /// let document = Cursor::new(b"hello world".to_vec());
+    /// let mut reader = ByteRange::new(None, 5).reader(document, 11).await?;
+    ///
+    /// let mut buf = String::new();
+    /// reader.read_to_string(&mut buf).await?;
+    /// assert_eq!(buf, "world");
+    /// # Ok(()) }) }
+    /// ```
+    pub async fn reader<R>(&self, mut reader: R, size: u64) -> crate::Result<io::Take<R>>
+    where
+        R: AsyncRead + AsyncSeek + Unpin,
+    {
+        let (start, end) = self.try_resolve(size)?;
+        reader.seek(io::SeekFrom::Start(start)).await?;
+        // Resolved bounds are ordered and within the document.
+        Ok(reader.take(end - start + 1))
+    }
+
+    /// Returns `true` if both ranges target the same bytes of a document of
+    /// the given size.
+    ///
+    /// Unlike `PartialEq`, which compares how the ranges are spelled, this
+    /// compares the resolved bounds. Unsatisfiable ranges never resolve the
+    /// same.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteRange;
+    ///
+    /// let suffix = ByteRange::new(None, 5);
+    /// let bounded = ByteRange::new(5, 9);
+    /// assert_ne!(suffix, bounded);
+    /// assert!(suffix.resolves_same(&bounded, 10));
+    /// ```
+    pub fn resolves_same(&self, other: &ByteRange, size: u64) -> bool {
+        match (self.resolve(size), other.resolve(size)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Split the range into up to `parts` roughly equal concrete ranges for a
+    /// document of the given size.
+    ///
+    /// The last range absorbs any remainder, so the union of the returned
+    /// ranges exactly covers the resolved range. Returns an empty `Vec` if the
+    /// range is not satisfiable or if `parts` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::ByteRange;
+    ///
+    /// let parts = ByteRange::new(0, None).split(10, 3);
+    /// assert_eq!(
+    ///     parts,
+    ///     vec![ByteRange::new(0, 2), ByteRange::new(3, 5), ByteRange::new(6, 9)]
+    /// );
+    /// ```
+    pub fn split(&self, size: u64, parts: usize) -> Vec<ByteRange> {
+        let (start, end, len) = match (self.resolve(size), self.len(size)) {
+            (Some((start, end)), Some(len)) if parts > 0 => (start, end, len),
+            _ => return Vec::new(),
+        };
+
+        let parts = (parts as u64).min(len);
+        let chunk = len / parts;
+        (0..parts)
+            .map(|n| {
+                let chunk_start = start + n * chunk;
+                let chunk_end = if n == parts - 1 {
+                    end
+                } else {
+                    chunk_start + chunk - 1
+                };
+                ByteRange::new(chunk_start, chunk_end)
+            })
+            .collect()
+    }
+
+    /// Parse a range in its bare `start-end` form, tolerating a single
+    /// leading `+` sign on each bound.
+    ///
+    /// Some lenient clients send signed bounds such as `+0-+5`. Strict
+    /// parsing through `FromStr` rejects those, as they may hide bugs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRange;
+    ///
+    /// assert!("+0-+5".parse::<ByteRange>().is_err());
+    /// assert_eq!(ByteRange::from_str_lenient("+0-+5")?, ByteRange::new(0, 5));
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn from_str_lenient(s: &str) -> crate::Result<Self> {
+        Self::parse(s, true)
+    }
+
+    fn parse(s: &str, lenient: bool) -> crate::Result<Self> {
+        if s.is_empty() {
+            return Err(RangeError::Malformed("Invalid Range header: empty range").into_error());
+        }
+
+        let mut parts = s.splitn(2, '-');
+        let start = str_to_bound(parts.next().unwrap_or(""), lenient)?;
+        let end = match parts.next() {
+            Some(s) => str_to_bound(s, lenient)?,
+            None => {
+                return Err(RangeError::Malformed(
+                    "Invalid Range header: range is missing a '-' separator",
+                )
+                .into_error())
+            }
+        };
+
+        Self::try_new(start, end)
+    }
+}
+
+impl FromStr for ByteRange {
+    type Err = crate::Error;
+
+    /// Parse a range in its bare `start-end` form.
+    ///
+    /// Syntax errors use `400 Bad Request`. The zero-length suffix range `-0`
+    /// parses, but never resolves.
+    fn from_str(s: &str) -> crate::Result<Self> {
+        Self::parse(s, false)
+    }
+}
+
+impl Display for ByteRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(start) = self.start {
+            write!(f, "{}", start)?;
+        }
+        f.write_char('-')?;
+        if let Some(end) = self.end {
+            write!(f, "{}", end)?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the range in its compact `start-end` form, the same as `Display`.
+impl Debug for ByteRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl TryFrom<Range<u64>> for ByteRange {
+    type Error = crate::Error;
+
+    /// Convert a half-open std range into an inclusive byte range.
+    ///
+    /// Returns an error if the range is empty, as it targets no bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::range::ByteRange;
+    /// use std::convert::TryFrom;
+    ///
+    /// assert_eq!(ByteRange::try_from(0..500)?, ByteRange::new(0, 499));
+    /// assert!(ByteRange::try_from(500..500).is_err());
+    /// #
+    /// # Ok(()) }
+    /// ```
+    fn try_from(range: Range<u64>) -> Result<Self, Self::Error> {
+        if range.start >= range.end {
+            return Err(RangeError::Malformed("Invalid range: range is empty").into_error());
+        }
+        Ok(Self::new(range.start, range.end - 1))
+    }
+}
+
+impl ToHeaderValues for ByteRange {
+    type Iter = option::IntoIter<HeaderValue>;
+    fn to_header_values(&self) -> crate::Result<Self::Iter> {
+        self.value().to_header_values()
+    }
+}
+
+impl Serialize for ByteRange {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+struct ByteRangeVisitor;
+
+impl<'de> Visitor<'de> for ByteRangeVisitor {
+    type Value = ByteRange;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a byte range as a `start-end` &str or a map")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: DeError,
+    {
+        ByteRange::from_str(v).map_err(|_| DeError::invalid_value(Unexpected::Str(v), &self))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut start = None;
+        let mut end = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "start" => start = map.next_value::<Option<u64>>()?,
+                "end" => end = map.next_value::<Option<u64>>()?,
+                _ => return Err(DeError::unknown_field(&key, &["start", "end"])),
+            }
+        }
+        ByteRange::try_new(start, end).map_err(DeError::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteRange {
+    /// Deserialize a range in its `start-end` string form, as serialized.
+    ///
+    /// Human-readable formats also accept a map with optional `start` and
+    /// `end` keys. Other formats, which are usually not self-describing, only
+    /// accept the string form.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ByteRangeVisitor)
+        } else {
+            deserializer.deserialize_str(ByteRangeVisitor)
+        }
+    }
+}
+
+/// Trim optional whitespace, which is made of spaces and tabs.
+fn trim_ows(s: &str) -> &str {
+    s.trim_matches(&[' ', '\t'][..])
+}
+
+fn str_to_bound(mut s: &str, lenient: bool) -> crate::Result<Option<u64>> {
+    if s.is_empty() {
+        return Ok(None);
+    }
+    if lenient {
+        s = s.strip_prefix('+').unwrap_or(s);
+    }
+    // `u64::from_str` accepts a leading `+`, which RFC 7233 does not allow.
+    if !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(
+            RangeError::Malformed("Invalid Range header: range bound is not a number").into_error(),
+        );
+    }
+    s.parse().map(Some).map_err(|_| {
+        RangeError::Malformed("Invalid Range header: range bound is not a number").into_error()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headers::Headers;
+
+    #[test]
+    fn smoke() -> crate::Result<()> {
+        let mut ranges = ByteRanges::new();
+        ranges.push(0, 499);
+        ranges.push(1000, None);
+        ranges.push(None, 500);
+
+        let mut headers = Headers::new();
+        ranges.apply(&mut headers);
+
+        assert_eq!(headers[RANGE], "bytes=0-499,1000-,-500");
+        let ranges = ByteRanges::from_headers(headers)?.unwrap();
+        let mut iter = ranges.iter();
+        assert_eq!(iter.next(), Some(&ByteRange::new(0, 499)));
+        assert_eq!(iter.next(), Some(&ByteRange::new(1000, None)));
+        assert_eq!(iter.next(), Some(&ByteRange::new(None, 500)));
+        assert_eq!(iter.next(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn unit() {
+        assert_eq!(ByteRanges::parse_unit("bytes=0-5"), Some(Unit::Bytes));
+        assert_eq!(
+            ByteRanges::parse_unit(" pages=1-5"),
+            Some(Unit::other("pages"))
+        );
+        assert_eq!(ByteRanges::parse_unit("pages"), None);
+        assert_eq!(ByteRanges::parse_unit("=1-5"), None);
+
+        let mut headers = Headers::new();
+        assert_eq!(ByteRanges::unit_from_headers(&headers), None);
+        headers.insert(RANGE, "pages=1-5");
+        assert_eq!(
+            ByteRanges::unit_from_headers(&headers),
+            Some(Unit::other("pages"))
+        );
+    }
+
+    #[test]
+    fn multiple_ranges_from_headers() -> crate::Result<()> {
+        let mut headers = Headers::new();
+        headers.insert(RANGE, "bytes=0-4,10-14");
+        let ranges = ByteRanges::from_headers(&headers)?.unwrap();
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(
+            ranges.iter().collect::<Vec<_>>(),
+            vec![&ByteRange::new(0, 4), &ByteRange::new(10, 14)]
+        );
+
+        headers.append(RANGE, "bytes=-5");
+        let ranges = ByteRanges::from_headers(&headers)?.unwrap();
+        assert_eq!(ranges.to_string(), "bytes=0-4,10-14,-5");
+
+        headers.append(RANGE, "pages=1-2");
+        assert!(ByteRanges::from_headers(&headers).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn empty_set_is_not_applied() {
+        let ranges = ByteRanges::new();
+        assert!(ranges.is_empty());
+        assert!(ranges.try_value().is_err());
+        assert!(ranges.to_header_values().is_err());
+
+        let mut headers = Headers::new();
+        ranges.apply(&mut headers);
+        assert!(headers.get(RANGE).is_none());
+    }
+
+    #[test]
+    fn byte_range_header_value() -> crate::Result<()> {
+        let mut headers = Headers::new();
+        headers.insert("x-resume-range", ByteRange::new(None, 500));
+        assert_eq!(headers["x-resume-range"], "-500");
+
+        let range: ByteRange = headers["x-resume-range"].as_str().parse()?;
+        assert_eq!(range, ByteRange::new(None, 500));
+        assert_eq!(ByteRange::new(0, 499).value(), "0-499");
+        assert_eq!(ByteRange::new(1000, None).value(), "1000-");
+        Ok(())
+    }
+
+    #[test]
+    fn other_unit_is_an_error() {
+        let mut headers = Headers::new();
+        headers.insert(RANGE, "pages=1-5");
+        let err = ByteRanges::from_headers(headers).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::InvalidUnit)
+        );
+
+        assert_eq!(ByteRanges::from_headers(Headers::new()).unwrap(), None);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = ByteRanges::from_str("bytes=5-1").unwrap_err();
+        assert_eq!(err.status(), 400);
+        let err = ByteRanges::from_str("bytes=-").unwrap_err();
+        assert_eq!(err.status(), 400);
+        let err = ByteRanges::from_str("bytes=a-5").unwrap_err();
+        assert_eq!(err.status(), 400);
+        let err = ByteRanges::from_str("bytes=5").unwrap_err();
+        assert_eq!(err.status(), 400);
+        let err = ByteRanges::from_str("0-5").unwrap_err();
+        assert_eq!(err.status(), 400);
+    }
+
+    #[test]
+    fn same_status_for_range_and_ranges() -> crate::Result<()> {
+        for s in &["a-5", "5", "", "5-1", "-", "0-x"] {
+            let err = ByteRange::from_str(s).unwrap_err();
+            assert_eq!(err.status(), 400, "ByteRange {:?}", s);
+            let err = ByteRanges::from_str(&format!("bytes={}", s)).unwrap_err();
+            assert_eq!(err.status(), 400, "ByteRanges {:?}", s);
+        }
+
+        let range = ByteRange::from_str("20-")?;
+        assert_eq!(range.check_size(10).unwrap_err().status(), 416);
+        let ranges = ByteRanges::from_str("bytes=20-")?;
+        assert_eq!(ranges.resolve(10).unwrap_err().status(), 416);
+        Ok(())
+    }
+
+    #[test]
+    fn to_content_ranges() -> crate::Result<()> {
+        let ranges = ByteRanges::from_str("bytes=-3,2-4,10-,5-")?;
+        let content_ranges = ranges.to_content_ranges(10);
+        assert_eq!(
+            content_ranges,
+            vec![
+                ByteContentRange::new().with_range(7, 9).with_size(10),
+                ByteContentRange::new().with_range(2, 4).with_size(10),
+                ByteContentRange::new().with_range(5, 9).with_size(10),
+            ]
+        );
+        assert!(ranges.to_content_ranges(0).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn plus_signs() -> crate::Result<()> {
+        for s in &["bytes=+0-5", "bytes=0-+5", "bytes=-+5", "bytes=++0-5"] {
+            let err = ByteRanges::from_str(s).unwrap_err();
+            assert_eq!(err.status(), 400);
+        }
+
+        let ranges = ByteRanges::from_str_lenient("bytes=+0-+5, -+3, +10-")?;
+        assert_eq!(ranges.to_string(), "bytes=0-5,-3,10-");
+        assert!(ByteRanges::from_str_lenient("bytes=++0-5").is_err());
+        assert!(ByteRanges::from_str_lenient("bytes=+-5").is_err());
+        assert!(ByteRanges::from_str_lenient("bytes=+0-+5,").is_err());
+
+        assert!(ByteRange::from_str("+1-2").is_err());
+        assert_eq!(ByteRange::from_str_lenient("+1-2")?, ByteRange::new(1, 2));
+        assert_eq!(ByteRange::from_str_lenient("1-2")?, ByteRange::new(1, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_segment_among_valid_ones() {
+        for s in &["bytes=0-4,abc-9", "bytes=abc-9,0-4", "bytes=0-4,5-x,10-14"] {
+            let err = ByteRanges::from_str(s).unwrap_err();
+            assert_eq!(err.status(), 400);
+            assert!(matches!(
+                err.downcast_ref::<RangeError>(),
+                Some(RangeError::Malformed(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn disallowing_suffix() -> crate::Result<()> {
+        let ranges = ByteRanges::from_str_disallowing_suffix("bytes=0-4,10-")?;
+        assert_eq!(ranges.len(), 2);
+
+        let err = ByteRanges::from_str_disallowing_suffix("bytes=-5").unwrap_err();
+        assert_eq!(err.status(), 400);
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Malformed(
+                "Invalid Range header: suffix ranges are not supported"
+            ))
+        );
+        let ranges = ByteRanges::from_str_disallowing_suffix("bytes=0-")?;
+        assert_eq!(ranges.len(), 1);
+        assert!(ByteRanges::from_str_disallowing_suffix("pages=0-4").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn contains() -> crate::Result<()> {
+        let range = ByteRange::new(10, 19);
+        assert!(!range.contains(9, 100));
+        assert!(range.contains(10, 100));
+        assert!(range.contains(19, 100));
+        assert!(!range.contains(20, 100));
+        assert!(!range.contains(10, 10));
+
+        let ranges = ByteRanges::from_str("bytes=0-1,5-6,200-")?;
+        let covered: Vec<u64> = (0..10).filter(|&n| ranges.contains_offset(n, 10)).collect();
+        assert_eq!(covered, vec![0, 1, 5, 6]);
+        assert!(!ranges.contains_offset(200, 10));
+        assert!(!ByteRanges::new().contains_offset(0, 10));
+        Ok(())
+    }
+
+    #[test]
+    fn requested_len() -> crate::Result<()> {
+        let ranges = ByteRanges::from_str("bytes=0-4,-5,0-4,200-")?;
+        assert_eq!(ranges.requested_len(10), Some(15));
+        assert_eq!(ByteRanges::new().requested_len(10), Some(0));
+
+        let mut ranges = ByteRanges::new();
+        ranges.push(0, None);
+        ranges.push(0, None);
+        assert_eq!(ranges.requested_len(u64::MAX), None);
+        Ok(())
+    }
+
+    #[test]
+    fn serde() -> Result<(), serde_json::Error> {
+        assert_eq!(
+            serde_json::to_string(&ByteRange::new(0, 499))?,
+            r#""0-499""#
+        );
+        assert_eq!(serde_json::to_string(&ByteRange::new(None, 5))?, r#""-5""#);
+
+        let range: ByteRange = serde_json::from_str(r#""0-499""#)?;
+        assert_eq!(range, ByteRange::new(0, 499));
+        let range: ByteRange = serde_json::from_str(r#""-5""#)?;
+        assert_eq!(range, ByteRange::new(None, 5));
+        let range: ByteRange = serde_json::from_str(r#"{"start":10,"end":null}"#)?;
+        assert_eq!(range, ByteRange::new(10, None));
+        let range: ByteRange = serde_json::from_str(r#"{"end":5}"#)?;
+        assert_eq!(range, ByteRange::new(None, 5));
+
+        assert!(serde_json::from_str::<ByteRange>(r#""5-1""#).is_err());
+        assert!(serde_json::from_str::<ByteRange>(r#"{"start":5,"end":1}"#).is_err());
+        assert!(serde_json::from_str::<ByteRange>(r#"{}"#).is_err());
+        assert!(serde_json::from_str::<ByteRange>(r#"{"begin":0}"#).is_err());
+        Ok(())
+    }
+
+    /// A deserializer for a non self-describing format, which can not guess
+    /// the type of the value to deserialize.
+    struct CompactDeserializer<'a>(&'a str);
+
+    impl<'de, 'a> Deserializer<'de> for CompactDeserializer<'a> {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            Err(DeError::custom("deserialize_any is not supported"))
+        }
+
+        fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.visit_str(self.0)
+        }
+
+        fn is_human_readable(&self) -> bool {
+            false
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct enum identifier ignored_any
+        }
+    }
+
+    #[test]
+    fn serde_non_self_describing() -> Result<(), serde::de::value::Error> {
+        for range in &[
+            ByteRange::new(0, 499),
+            ByteRange::new(None, 5),
+            ByteRange::new(10, None),
+        ] {
+            let serialized = range.to_string();
+            let deserialized = ByteRange::deserialize(CompactDeserializer(&serialized))?;
+            assert_eq!(deserialized, *range);
+        }
+        assert!(ByteRange::deserialize(CompactDeserializer("5-1")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_bounded() -> crate::Result<()> {
+        let ranges = ByteRanges::from_str_bounded("bytes=0-9,0-9", 10, 20)?;
+        assert_eq!(ranges.len(), 2);
+
+        let err = ByteRanges::from_str_bounded("bytes=0-9,0-9,0-0", 10, 20).unwrap_err();
+        assert_eq!(err.status(), 400);
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::TooLarge)
+        );
+
+        let err = ByteRanges::from_str_bounded("bytes=0-4,20-", 10, 20).unwrap_err();
+        assert_eq!(err.status(), 416);
+        assert!(ByteRanges::from_str_bounded("bytes=0-", u64::MAX, u64::MAX).is_ok());
+        let err = ByteRanges::from_str_bounded("bytes=a-", 10, 20).unwrap_err();
+        assert_eq!(err.status(), 400);
+        Ok(())
+    }
+
+    #[test]
+    fn ranges_serde() -> Result<(), serde_json::Error> {
+        let ranges: ByteRanges = vec![ByteRange::new(0, 4), ByteRange::new(None, 5)]
+            .into_iter()
+            .collect();
+        let json = serde_json::to_string(&ranges)?;
+        assert_eq!(json, r#"["0-4","-5"]"#);
+        assert_eq!(serde_json::from_str::<ByteRanges>(&json)?, ranges);
+
+        let ranges: ByteRanges = serde_json::from_str(r#"[{"start":10},"-1"]"#)?;
+        assert_eq!(ranges, "bytes=10-,-1");
+
+        assert!(serde_json::from_str::<ByteRanges>("[]").is_err());
+        assert!(serde_json::from_str::<ByteRanges>(r#"["5-1"]"#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn empty_and_missing_dash() {
+        let err = ByteRange::from_str("").unwrap_err();
+        assert_eq!(err.status(), 400);
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Malformed("Invalid Range header: empty range"))
+        );
+        assert_eq!(ByteRange::from_str("  ").unwrap_err().status(), 400);
+
+        let err = ByteRange::from_str("5").unwrap_err();
+        assert_eq!(err.status(), 400);
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Malformed(
+                "Invalid Range header: range is missing a '-' separator"
+            ))
+        );
+        let err = ByteRanges::from_str("bytes=5").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Malformed(
+                "Invalid Range header: range is missing a '-' separator"
+            ))
+        );
+    }
+
+    #[test]
+    fn case_insensitive_unit() -> crate::Result<()> {
+        for s in &["Bytes=0-10", "BYTES=0-10", "bYtEs=0-10"] {
+            let ranges = ByteRanges::from_str(s)?;
+            assert_eq!(ranges, "bytes=0-10");
+        }
+
+        let mut headers = Headers::new();
+        headers.insert(RANGE, "BYTES=0-10");
+        let ranges = ByteRanges::from_headers(&headers)?.unwrap();
+        assert_eq!(ranges.first(), Some(ByteRange::new(0, 10)));
+        assert_eq!(ByteRanges::unit_from_headers(&headers), Some(Unit::Bytes));
+
+        let err = ByteRanges::from_str("Bytés=0-10").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::InvalidUnit)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn optional_whitespace() -> crate::Result<()> {
+        let expected = [ByteRange::new(0, 4), ByteRange::new(5, 9)];
+        for s in &[
+            "bytes=0-4,\t5-9",
+            "bytes=0-4\t,5-9",
+            "bytes=0-4  ,   5-9",
+            "bytes= \t0-4,5-9\t ",
+            " bytes=0-4, 5-9",
+        ] {
+            assert_eq!(ByteRanges::from_str(s)?.as_slice(), &expected);
+        }
+
+        let err = ByteRanges::from_str("bytes=0-4,\n5-9").unwrap_err();
+        assert_eq!(err.status(), 400);
+        assert!(ByteRanges::from_str("bytes =0-4").is_err());
+
+        // Whitespace is not allowed inside a range.
+        for s in &[
+            "bytes=0 - 4",
+            "bytes=0 -4",
+            "bytes=0-\t4",
+            "bytes=- 5",
+            "bytes=1 0-",
+        ] {
+            let err = ByteRanges::from_str(s).unwrap_err();
+            assert_eq!(err.status(), 400, "{}", s);
+        }
+        assert!(ByteRange::from_str(" 0-4").is_err());
+        assert!(ByteRange::from_str_lenient("+0 -+5").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn empty_segments() {
+        for s in &[
+            "bytes=1-2,",
+            "bytes=,1-2",
+            "bytes=1-2,,3-4",
+            "bytes=0-4, ,5-9",
+            "bytes=",
+        ] {
+            let err = ByteRanges::from_str(s).unwrap_err();
+            assert_eq!(err.status(), 400);
+            assert_eq!(
+                err.downcast_ref::<RangeError>(),
+                Some(&RangeError::Malformed(
+                    "Invalid Range header: range list has an empty element"
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn range_error() {
+        let err = ByteRanges::from_str("bytes=5-1").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Reversed)
+        );
+        let err = ByteRanges::from_str("pages=5-1").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::InvalidUnit)
+        );
+        let err = ByteRanges::from_str("bytes=a-1").unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<RangeError>(),
+            Some(RangeError::Malformed(_))
+        ));
+
+        let ranges = ByteRanges::from_str("bytes=20-").unwrap();
+        let err = ranges.resolve(10).unwrap_err();
+        assert_eq!(err.status(), 416);
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Unsatisfiable(
+                "Range not satisfiable: range start exceeds document size"
+            ))
+        );
+    }
+
+    #[test]
+    fn split() {
+        let range = ByteRange::new(10, 19);
+        assert_eq!(range.split(100, 1), vec![ByteRange::new(10, 19)]);
+        assert_eq!(
+            range.split(100, 3),
+            vec![
+                ByteRange::new(10, 12),
+                ByteRange::new(13, 15),
+                ByteRange::new(16, 19)
+            ]
+        );
+
+        let parts = ByteRange::new(None, 3).split(10, 5);
+        assert_eq!(
+            parts,
+            vec![
+                ByteRange::new(7, 7),
+                ByteRange::new(8, 8),
+                ByteRange::new(9, 9)
+            ]
+        );
+
+        assert!(range.split(100, 0).is_empty());
+        assert!(range.split(5, 2).is_empty());
+    }
+
+    #[test]
+    fn chunked() -> crate::Result<()> {
+        let ranges = ByteRanges::from_str("bytes=0-15,20-22,-5,200-")?;
+        assert_eq!(
+            ranges.chunked(100, 8).to_string(),
+            "bytes=0-7,8-15,20-22,95-95,96-99"
+        );
+        assert_eq!(
+            ranges.chunked(100, 1000).to_string(),
+            "bytes=0-15,20-22,95-99"
+        );
+        assert_eq!(ranges.chunked(100, 0), ByteRanges::new());
+        Ok(())
+    }
+
+    #[test]
+    fn check_size() {
+        let assert_reason = |range: ByteRange, size, reason: &str| {
+            let err = range.check_size(size).unwrap_err();
+            assert_eq!(err.status(), 416);
+            assert_eq!(err.to_string(), reason);
+        };
+        assert!(ByteRange::new(0, 9).check_size(10).is_ok());
+        assert_reason(
+            ByteRange::new(0, 9),
+            0,
+            "Range not satisfiable: document is empty",
+        );
+        assert_reason(
+            ByteRange::new(10, None),
+            10,
+            "Range not satisfiable: range start exceeds document size",
+        );
+        assert!(ByteRange::new(0, 10).check_size(10).is_ok());
+        assert_reason(
+            ByteRange::new(None, 0),
+            10,
+            "Range not satisfiable: suffix length is zero",
+        );
+    }
+
+    #[test]
+    fn dedup() -> crate::Result<()> {
+        let mut ranges = ByteRanges::from_str("bytes=0-4,0-4")?;
+        ranges.dedup();
+        assert_eq!(ranges.to_string(), "bytes=0-4");
+
+        let mut ranges = ByteRanges::from_str("bytes=-5,0-4,5-9,-5,0-4")?;
+        ranges.dedup();
+        assert_eq!(ranges.to_string(), "bytes=-5,0-4,5-9");
+        Ok(())
+    }
+
+    #[test]
+    fn canonical() -> crate::Result<()> {
+        let a = ByteRanges::from_str("bytes=10-14,0-4,-5")?;
+        let b = ByteRanges::from_str("bytes=95-99,0-3,4-4,10-14")?;
+        assert_ne!(a.to_string(), b.to_string());
+        assert_eq!(a.canonical(100).to_string(), "bytes=0-4,10-14,95-99");
+        assert_eq!(a.canonical(100), b.canonical(100));
+
+        let mut ranges = ByteRanges::from_str("bytes=0-4,5-9,20-")?;
+        ranges.coalesce(15);
+        assert_eq!(ranges.to_string(), "bytes=0-9");
+        Ok(())
+    }
+
+    #[test]
+    fn suffix_bounds() -> crate::Result<()> {
+        let range = ByteRange::from_str("-0")?;
+        assert!(!range.match_size(10));
+        assert_eq!(range.check_size(10).unwrap_err().status(), 416);
+
+        let range = ByteRange::from_str("-18446744073709551615")?;
+        assert!(range.match_size(10));
+        assert_eq!(range.resolve(10), Some((0, 9)));
+        assert_eq!(range.resolve(u64::MAX), Some((0, u64::MAX - 1)));
+        Ok(())
+    }
+
+    #[test]
+    fn max_bounds_do_not_overflow() -> crate::Result<()> {
+        let range = ByteRange::from_str("0-18446744073709551615")?;
+        assert_eq!(range.end, Some(u64::MAX));
+        assert_eq!(range.len(u64::MAX), Some(u64::MAX));
+        assert_eq!(range.resolve(u64::MAX), Some((0, u64::MAX - 1)));
+        assert_eq!(range.split(u64::MAX, 4).len(), 4);
+
+        let range = ByteRange::new(0, u64::MAX - 1);
+        assert_eq!(range.len(u64::MAX), Some(u64::MAX));
+        assert_eq!(range.split(u64::MAX, 2).len(), 2);
+        assert_eq!(ByteRange::new(u64::MAX - 1, None).len(u64::MAX), Some(1));
+
+        let reversed = ByteRange::new(5, 3);
+        assert_eq!(reversed.len(10), None);
+        assert!(reversed.split(10, 2).is_empty());
+
+        let mut ranges =
+            ByteRanges::from_str("bytes=0-18446744073709551615,18446744073709551614-")?;
+        assert_eq!(
+            ranges.resolve(u64::MAX)?,
+            vec![(0, u64::MAX - 1), (u64::MAX - 1, u64::MAX - 1)]
+        );
+        assert_eq!(ranges.chunked(u64::MAX, u64::MAX).len(), 2);
+        ranges.coalesce(u64::MAX);
+        assert_eq!(ranges.to_string(), "bytes=0-18446744073709551614");
+
+        let content_range = ByteContentRange::new().with_range(0, u64::MAX);
+        assert!(content_range.validate_body_len(u64::MAX).is_err());
+        assert_eq!(content_range.to_string(), "bytes 0-18446744073709551615/*");
+        Ok(())
+    }
+
+    #[test]
+    fn oversized_suffix_serves_whole_document() -> crate::Result<()> {
+        let ranges = ByteRanges::from_str("bytes=-500")?;
+        assert!(ranges.match_size(100));
+        assert_eq!(ranges.resolve(100)?, vec![(0, 99)]);
+        Ok(())
+    }
+
+    #[test]
+    fn parts() -> crate::Result<()> {
+        let ranges = ByteRanges::from_str("bytes=0-0,-2")?;
+        let parts = ranges.parts(10, "image/png")?;
+        assert_eq!(
+            parts,
+            vec![
+                (
+                    ByteContentRange::new().with_range(0, 0).with_size(10),
+                    String::from("Content-Type: image/png\r\nContent-Range: bytes 0-0/10\r\n\r\n"),
+                ),
+                (
+                    ByteContentRange::new().with_range(8, 9).with_size(10),
+                    String::from("Content-Type: image/png\r\nContent-Range: bytes 8-9/10\r\n\r\n"),
+                ),
+            ]
+        );
+
+        let ranges = ByteRanges::from_str("bytes=0-0,20-")?;
+        let err = ranges.parts(10, "image/png").unwrap_err();
+        assert_eq!(err.status(), 416);
+        Ok(())
+    }
+
+    #[test]
+    fn retain_satisfiable_and_limit() -> crate::Result<()> {
+        let mut ranges = ByteRanges::from_str("bytes=20-,5-9,10-12,-3,0-0")?;
+        ranges.retain_satisfiable(10);
+        assert_eq!(ranges.to_string(), "bytes=5-9,-3,0-0");
+
+        ranges.limit(5);
+        assert_eq!(ranges.len(), 3);
+        ranges.limit(2);
+        assert_eq!(ranges.to_string(), "bytes=5-9,-3");
+        ranges.limit(0);
+        assert!(ranges.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn coalesce_many_overlapping_ranges() {
+        // Each range overlaps the next one by a single byte, in reverse
+        // order, which is the worst case for a naive merge.
+        let count = 100_000;
+        let mut ranges = ByteRanges::new();
+        for i in (0..count).rev() {
+            ranges.push(i * 10, i * 10 + 10);
+        }
+
+        ranges.coalesce(count * 10 + 1);
+        assert_eq!(ranges.as_slice(), &[ByteRange::new(0, count * 10)]);
+    }
+
+    #[test]
+    fn coverage_and_gaps() -> crate::Result<()> {
+        let ranges = ByteRanges::from_str("bytes=-2,3-4,20-")?;
+        assert_eq!(ranges.coverage(10), vec![(3, 4), (8, 9)]);
+        assert_eq!(ranges.gaps(10), vec![(0, 2), (5, 7)]);
+
+        let ranges = ByteRanges::from_str("bytes=0-")?;
+        assert_eq!(ranges.coverage(10), vec![(0, 9)]);
+        assert!(ranges.gaps(10).is_empty());
+        assert!(ranges.coverage(0).is_empty());
+        assert!(ranges.gaps(0).is_empty());
+
+        let ranges = ByteRanges::new();
+        assert!(ranges.coverage(10).is_empty());
+        assert_eq!(ranges.gaps(10), vec![(0, 9)]);
+        Ok(())
+    }
+
+    #[test]
+    fn insert_covered() {
+        let mut ranges = ByteRanges::new();
+        ranges.insert_covered(ByteRange::new(5, 9), 20);
+        ranges.insert_covered(ByteRange::new(0, 2), 20);
+        assert_eq!(ranges.to_string(), "bytes=0-2,5-9");
+
+        ranges.insert_covered(ByteRange::new(30, None), 20);
+        assert_eq!(ranges.to_string(), "bytes=0-2,5-9");
+
+        ranges.insert_covered(ByteRange::new(3, 4), 20);
+        assert_eq!(ranges.to_string(), "bytes=0-9");
+        assert_eq!(ranges.gaps(20), vec![(10, 19)]);
+    }
+
+    #[test]
+    fn equivalent() -> crate::Result<()> {
+        let a = ByteRanges::from_str("bytes=0-4,10-14")?;
+        let b = ByteRanges::from_str("bytes=10-14,0-2,3-4")?;
+        let c = ByteRanges::from_str("bytes=-10")?;
+        assert!(a.equivalent(&b, 100));
+        assert!(!a.equivalent(&c, 100));
+        assert!(c.equivalent(&ByteRanges::from_str("bytes=0-")?, 10));
+        assert!(a.equivalent(&a, 100));
+        Ok(())
+    }
+
+    #[test]
+    fn try_push() -> crate::Result<()> {
+        let mut ranges = ByteRanges::new();
+        ranges.try_push(0, 9, 100)?;
+        ranges.try_push(10, 19, 100)?;
+        ranges.try_push(90, None, 100)?;
+        assert_eq!(ranges.to_string(), "bytes=0-9,10-19,90-");
+
+        let err = ranges.try_push(None, 5, 100).unwrap_err();
+        assert_eq!(err.status(), 500);
+        let err = ranges.try_push(0, 4, 100).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Unordered)
+        );
+        let err = ranges.try_push(200, 300, 100).unwrap_err();
+        assert_eq!(err.status(), 416);
+        assert_eq!(ranges.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn as_slice() -> crate::Result<()> {
+        let ranges = ByteRanges::from_str("bytes=0-4,10-14")?;
+        assert_eq!(
+            ranges.as_slice(),
+            &[ByteRange::new(0, 4), ByteRange::new(10, 14)]
+        );
+        assert!(ByteRanges::new().as_slice().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn single_and_multi() -> crate::Result<()> {
+        let ranges = ByteRanges::new();
+        assert!(!ranges.is_single());
+        assert!(!ranges.is_multi());
+
+        let ranges = ByteRanges::from_str("bytes=0-4")?;
+        assert!(ranges.is_single());
+        assert!(!ranges.is_multi());
+
+        let mut ranges = ByteRanges::from_str("bytes=0-4,20-29")?;
+        assert!(!ranges.is_single());
+        assert!(ranges.is_multi());
+
+        ranges.coalesce(10);
+        assert!(ranges.is_single());
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn reader() -> crate::Result<()> {
+        let document = io::Cursor::new((0..10).collect::<Vec<u8>>());
+        let mut reader = ByteRange::new(2, 4).reader(document, 10).await?;
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await?;
+        assert_eq!(buf, vec![2, 3, 4]);
+
+        let document = io::Cursor::new((0..10).collect::<Vec<u8>>());
+        let mut reader = ByteRange::new(7, None).reader(document, 10).await?;
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await?;
+        assert_eq!(buf, vec![7, 8, 9]);
+
+        let document = io::Cursor::new((0..10).collect::<Vec<u8>>());
+        let err = ByteRange::new(20, None)
+            .reader(document, 10)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), 416);
+        Ok(())
+    }
+
+    #[test]
+    fn try_new() -> crate::Result<()> {
+        assert_eq!(ByteRange::try_new(1, 5)?, ByteRange::new(1, 5));
+        assert_eq!(ByteRange::try_new(1, None)?, ByteRange::new(1, None));
+        assert_eq!(ByteRange::try_new(None, 5)?, ByteRange::new(None, 5));
+        assert_eq!(ByteRange::try_new(5, 5)?, ByteRange::new(5, 5));
+
+        let err = ByteRange::try_new(5, 1).unwrap_err();
+        assert_eq!(err.status(), 400);
+        assert_eq!(ByteRange::try_new(None, 0)?, ByteRange::new(None, 0));
+
+        let err = ByteRange::try_new(None, None).unwrap_err();
+        assert_eq!(err.status(), 400);
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Malformed(
+                "Invalid Range header: range has no bounds"
+            ))
+        );
+        assert!(ByteRange::new(None, None).resolve(10).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn to_content_range() -> crate::Result<()> {
+        let content_range = ByteRange::new(5, None).to_content_range(10)?;
+        assert_eq!(content_range.range(), Some((5, 9)));
+        assert_eq!(content_range.size(), Some(10));
+
+        let err = ByteRange::new(10, None).to_content_range(10).unwrap_err();
+        assert_eq!(err.status(), 416);
+        Ok(())
+    }
+
+    #[test]
+    fn resume() {
+        assert_eq!(ByteRange::resume_from(0), ByteRange::new(0, None));
+        assert_eq!(ByteRange::resume_from(10), ByteRange::new(10, None));
+        assert_eq!(ByteRange::resume_bounded(0, 10), Some(ByteRange::new(0, 9)));
+        assert_eq!(ByteRange::resume_bounded(9, 10), Some(ByteRange::new(9, 9)));
+        assert_eq!(ByteRange::resume_bounded(10, 10), None);
+        assert_eq!(ByteRange::resume_bounded(0, 0), None);
+    }
+
+    #[test]
+    fn resolves_same() {
+        let suffix = ByteRange::new(None, 5);
+        let bounded = ByteRange::new(5, 9);
+        let open = ByteRange::new(5, None);
+        assert!(suffix.resolves_same(&bounded, 10));
+        assert!(bounded.resolves_same(&suffix, 10));
+        assert!(open.resolves_same(&suffix, 10));
+        assert!(!suffix.resolves_same(&bounded, 20));
+        assert!(!ByteRange::new(20, 30).resolves_same(&ByteRange::new(20, 30), 10));
+    }
+
+    #[test]
+    fn to_range_list_string() -> crate::Result<()> {
+        let ranges = ByteRanges::from_str("bytes=0-4,-5,10-")?;
+        assert_eq!(ranges.to_range_list_string(), "0-4,-5,10-");
+        assert_eq!(ByteRanges::new().to_range_list_string(), "");
+        Ok(())
+    }
+
+    #[test]
+    fn to_header_string() {
+        assert_eq!(ByteRange::new(1, 5).to_header_string(), "1-5");
+        assert_eq!(ByteRange::new(None, 5).to_header_string(), "-5");
+        assert_eq!(ByteRange::new(1, None).to_header_string(), "1-");
+    }
+
+    #[test]
+    fn display_round_trip() -> crate::Result<()> {
+        let cases = [
+            ("1-", ByteRange::new(1, None)),
+            ("-5", ByteRange::new(None, 5)),
+            ("1-5", ByteRange::new(1, 5)),
+        ];
+        for (s, range) in cases.iter() {
+            assert_eq!(range.to_string(), *s);
+            assert_eq!(ByteRange::from_str(s)?, *range);
+            assert_eq!(ByteRange::from_str(&range.to_string())?, *range);
+        }
+
+        let ranges = ByteRanges::from_str("bytes=1-,-5,1-5")?;
+        assert_eq!(ranges.as_slice(), &[cases[0].1, cases[1].1, cases[2].1]);
+        assert_eq!(ByteRanges::from_str(&ranges.to_string())?, ranges);
+        Ok(())
+    }
+
+    #[test]
+    fn from_byte_range_and_push_range() -> crate::Result<()> {
+        let suffix = ByteRange::new(None, 500);
+
+        let ranges = ByteRanges::from(suffix);
+        assert_eq!(ranges, "bytes=-500");
+        assert_eq!(
+            ByteRanges::from_str(&ranges.to_string())?.first(),
+            Some(suffix)
+        );
+
+        let mut ranges = ByteRanges::new();
+        ranges.push_range(ByteRange::new(0, 4));
+        ranges.push_range(suffix);
+        assert_eq!(ranges, "bytes=0-4,-500");
+        let parsed = ByteRanges::from_str(&ranges.to_string())?;
+        assert_eq!(parsed.as_slice(), &[ByteRange::new(0, 4), suffix]);
+        Ok(())
+    }
+
+    #[test]
+    fn collect_and_extend() -> crate::Result<()> {
+        let ranges: ByteRanges = ByteRanges::from_str("bytes=0-4,-5")?.into_iter().collect();
+        assert_eq!(ranges, "bytes=0-4,-5");
+
+        let mut ranges: ByteRanges = std::iter::once(ByteRange::new(0, 4)).collect();
+        ranges.extend(vec![ByteRange::new(10, None), ByteRange::new(None, 1)]);
+        assert_eq!(
+            ranges.as_slice(),
+            &[
+                ByteRange::new(0, 4),
+                ByteRange::new(10, None),
+                ByteRange::new(None, 1)
+            ]
+        );
+        assert!(std::iter::empty::<ByteRange>()
+            .collect::<ByteRanges>()
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn from_std_range() -> crate::Result<()> {
+        assert_eq!(ByteRange::try_from(5..6)?, ByteRange::new(5, 5));
+        assert_eq!(
+            ByteRange::try_from(0..u64::MAX)?,
+            ByteRange::new(0, u64::MAX - 1)
+        );
+
+        let err = ByteRange::try_from(5..5).unwrap_err();
+        assert_eq!(err.status(), 400);
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 6..5;
+        assert!(ByteRange::try_from(reversed).is_err());
+
+        let ranges: ByteRanges = vec![0..10, 15..15, 20..21].into_iter().collect();
+        assert_eq!(
+            ranges.as_slice(),
+            &[ByteRange::new(0, 9), ByteRange::new(20, 20)]
+        );
+        assert!(std::iter::empty::<Range<u64>>()
+            .collect::<ByteRanges>()
+            .is_empty());
+
+        let ranges: ByteRanges = vec![5..5, 8..8].into_iter().collect();
+        assert!(ranges.is_empty());
+        assert_eq!(ranges.try_value().unwrap_err().status(), 500);
+
+        let ranges: crate::Result<ByteRanges> = vec![0..10, 5..5]
+            .into_iter()
+            .map(ByteRange::try_from)
+            .collect();
+        assert_eq!(ranges.unwrap_err().status(), 400);
+        let ranges: crate::Result<ByteRanges> = vec![0..10, 20..30]
+            .into_iter()
+            .map(ByteRange::try_from)
+            .collect();
+        assert_eq!(
+            ranges?.as_slice(),
+            &[ByteRange::new(0, 9), ByteRange::new(20, 29)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ranges_unit() -> crate::Result<()> {
+        assert_eq!(ByteRanges::new().unit(), Unit::Bytes);
+        assert_eq!(ByteRanges::from_str("bytes=0-4")?.unit(), Unit::Bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn to_std_range() {
+        assert_eq!(ByteRange::new(0, 4).to_std_range(10), Some(0..5));
+        assert_eq!(ByteRange::new(5, None).to_std_range(10), Some(5..10));
+        assert_eq!(ByteRange::new(None, 20).to_std_range(10), Some(0..10));
+        assert_eq!(ByteRange::new(10, None).to_std_range(10), None);
+        assert_eq!(
+            ByteRange::new(0, u64::MAX - 1).to_std_range(u64::MAX),
+            Some(0..u64::MAX)
+        );
+        assert_eq!(ByteRange::new(u64::MAX, None).to_std_range(u64::MAX), None);
+    }
+
+    #[test]
+    fn is_full() {
+        assert!(ByteRange::new(0, 1000).is_full(100));
+        assert!(ByteRange::new(None, 1000).is_full(100));
+        assert!(ByteRange::new(0, 0).is_full(1));
+        assert!(!ByteRange::new(0, 98).is_full(100));
+        assert!(!ByteRange::new(None, 99).is_full(100));
+        assert!(!ByteRange::new(0, None).is_full(0));
+        assert!(!ByteRange::new(5, 1).is_full(100));
+    }
+
+    #[test]
+    fn parse_strict() -> crate::Result<()> {
+        let ranges = ByteRanges::parse_strict("bytes=5-9,-2,0-6", 10, StrictOpts::new())?;
+        assert_eq!(ranges.to_string(), "bytes=0-9");
+
+        let opts = StrictOpts::new().allow_suffix(false);
+        let err = ByteRanges::parse_strict("bytes=0-4,-2", 10, opts).unwrap_err();
+        assert_eq!(err.status(), 400);
+        assert!(ByteRanges::parse_strict("bytes=0-4,2-", 10, opts).is_ok());
+
+        let opts = StrictOpts::new().with_max_count(2);
+        let err = ByteRanges::parse_strict("bytes=0-1,2-3,4-5", 10, opts).unwrap_err();
+        assert_eq!(err.status(), 400);
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::TooManyRanges)
+        );
+
+        let opts = StrictOpts::new().with_max_total_len(10);
+        let err = ByteRanges::parse_strict("bytes=0-,0-0", 10, opts).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::TooLarge)
+        );
+        assert!(ByteRanges::parse_strict("bytes=0-", 10, opts).is_ok());
+
+        let opts = StrictOpts::new().allow_overlap(false);
+        let err = ByteRanges::parse_strict("bytes=5-9,-5", 10, opts).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Malformed(
+                "Invalid Range header: ranges overlap"
+            ))
+        );
+        let ranges = ByteRanges::parse_strict("bytes=5-9,0-4", 10, opts)?;
+        assert_eq!(ranges.to_string(), "bytes=0-9");
+
+        let err = ByteRanges::parse_strict("bytes=20-", 10, StrictOpts::new()).unwrap_err();
+        assert_eq!(err.status(), 416);
+        Ok(())
+    }
+
+    #[test]
+    fn eq_str() -> crate::Result<()> {
+        let ranges = ByteRanges::from_str("bytes=0-4, -5")?;
+        assert_eq!(ranges, "bytes=0-4,-5");
+        assert_eq!(ranges, *"bytes=0-4,-5");
+        assert_ne!(ranges, "bytes=0-4, -5");
+        Ok(())
+    }
+
+    #[test]
+    fn debug() {
+        assert_eq!(format!("{:?}", ByteRange::new(1, 5)), "1-5");
+        assert_eq!(format!("{:?}", ByteRange::new(None, 5)), "-5");
+        assert_eq!(format!("{:?}", ByteRange::new(1, None)), "1-");
+        assert_eq!(
+            format!("{:?}", vec![ByteRange::new(0, 4), ByteRange::new(None, 1)]),
+            "[0-4, -1]"
+        );
+    }
+
+    #[test]
+    fn reversed_range_is_not_satisfiable() {
+        let range = ByteRange::new(5, 1);
+        assert!(!range.match_size(10));
+        assert_eq!(range.resolve(10), None);
+        let err = range.check_size(10).unwrap_err();
+        assert_eq!(err.status(), 416);
+        assert_eq!(
+            err.to_string(),
+            "Range not satisfiable: range start exceeds range end"
+        );
+    }
+
+    #[test]
+    fn match_size() {
+        assert!(ByteRange::new(0, 9).match_size(10));
+        assert!(ByteRange::new(0, 10).match_size(10));
+        assert!(ByteRange::new(9, None).match_size(10));
+        assert!(!ByteRange::new(10, None).match_size(10));
+        assert!(ByteRange::new(None, 10).match_size(10));
+        assert!(!ByteRange::new(0, 0).match_size(0));
+    }
+
+    #[test]
+    fn iter_resolved() -> crate::Result<()> {
+        let ranges = ByteRanges::from_str("bytes=0-4,-5,7-,20-")?;
+        let mut iter = ranges.iter_resolved(10);
+        assert_eq!(iter.next().unwrap()?, (0, 4));
+        assert_eq!(iter.next().unwrap()?, (5, 9));
+        assert_eq!(iter.next().unwrap()?, (7, 9));
+        assert_eq!(iter.next().unwrap().unwrap_err().status(), 416);
+        assert!(iter.next().is_none());
+
+        assert!(ranges.resolve(10).is_err());
+        assert_eq!(
+            ranges.resolve(30)?,
+            vec![(0, 4), (25, 29), (7, 29), (20, 29)]
+        );
+        Ok(())
+    }
+}
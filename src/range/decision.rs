@@ -0,0 +1,240 @@
+use crate::range::{ByteContentRange, ByteRanges, RangeRequestExt};
+use crate::{Method, Request, StatusCode};
+
+/// How a server should respond to a request, based on its `Range` header.
+///
+/// This is returned by [`evaluate`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeDecision {
+    /// Send the full representation with a `200 OK` status.
+    Full,
+    /// Send the satisfiable ranges with a `206 Partial Content` status.
+    ///
+    /// The ranges are resolved into concrete bounds, in the order they were
+    /// requested.
+    Partial(ByteRanges),
+    /// Send a `416 Requested Range Not Satisfiable` status.
+    Unsatisfiable,
+}
+
+impl RangeDecision {
+    /// Returns the status code of the response.
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::Full => StatusCode::Ok,
+            Self::Partial(_) => StatusCode::PartialContent,
+            Self::Unsatisfiable => StatusCode::RequestedRangeNotSatisfiable,
+        }
+    }
+
+    /// Returns the `Content-Range` header of the response, for a document of
+    /// the given size.
+    ///
+    /// A partial response with a single range carries its range, while an
+    /// unsatisfiable response carries the complete size of the document.
+    /// Returns `None` for a full response, and for a partial response with
+    /// multiple ranges, whose parts each carry their own `Content-Range`.
+    pub fn content_range(&self, size: u64) -> Option<ByteContentRange> {
+        match self {
+            Self::Partial(ranges) if ranges.is_single() => {
+                ranges.first()?.to_content_range(size).ok()
+            }
+            Self::Unsatisfiable => Some(ByteContentRange::unsatisfied(size)),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the representation should be sent in the response
+    /// body.
+    ///
+    /// Responses to `HEAD` requests have the same status and headers as the
+    /// corresponding `GET` response, but no body. Unsatisfiable responses do
+    /// not carry the representation either.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> {
+    /// #
+    /// use http_types::{Method, Request, StatusCode, Url};
+    /// use http_types::range;
+    ///
+    /// let mut req = Request::new(Method::Head, Url::parse("https://example.com")?);
+    /// req.insert_header("Range", "bytes=0-9");
+    ///
+    /// let decision = range::evaluate(&req, 100);
+    /// assert_eq!(decision.status(), StatusCode::PartialContent);
+    /// assert_eq!(decision.content_range(100).unwrap().to_string(), "bytes 0-9/100");
+    /// assert!(!decision.send_body(&req));
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn send_body(&self, req: &Request) -> bool {
+        match self {
+            Self::Unsatisfiable => false,
+            _ => req.method() != Method::Head,
+        }
+    }
+}
+
+/// Decide how to respond to a request for a document of the given size.
+///
+/// - Without a `Range` header, or for methods other than `GET` and `HEAD`, the
+///   full representation is sent.
+/// - A `Range` header using an unsupported unit or an invalid syntax is
+///   ignored, and the full representation is sent.
+/// - Otherwise, unsatisfiable ranges are dropped and the remaining ranges are
+///   sent as a partial response. If no range is satisfiable, the request is
+///   unsatisfiable.
+///
+/// Preconditions such as `If-Range` are not evaluated, and should be checked
+/// beforehand. `HEAD` requests are evaluated like `GET` requests; use
+/// [`RangeDecision::send_body`] to know whether to send a body.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::{Method, Request, Url};
+/// use http_types::range::{self, RangeDecision};
+///
+/// let mut req = Request::new(Method::Get, Url::parse("https://example.com")?);
+/// assert_eq!(range::evaluate(&req, 100), RangeDecision::Full);
+///
+/// req.insert_header("Range", "bytes=-10,200-");
+/// match range::evaluate(&req, 100) {
+///     RangeDecision::Partial(ranges) => assert_eq!(ranges.to_string(), "bytes=90-99"),
+///     _ => unreachable!(),
+/// }
+///
+/// req.insert_header("Range", "bytes=200-");
+/// assert_eq!(range::evaluate(&req, 100), RangeDecision::Unsatisfiable);
+/// #
+/// # Ok(()) }
+/// ```
+pub fn evaluate(req: &Request, size: u64) -> RangeDecision {
+    // Range requests are only defined for GET, and HEAD mirrors GET.
+    if !matches!(req.method(), Method::Get | Method::Head) {
+        return RangeDecision::Full;
+    }
+
+    let ranges = match req.byte_ranges() {
+        Ok(Some(ranges)) => ranges,
+        Ok(None) | Err(_) => return RangeDecision::Full,
+    };
+
+    let mut resolved = ByteRanges::new();
+    for (start, end) in ranges.iter().filter_map(|range| range.resolve(size)) {
+        resolved.push(start, end);
+    }
+
+    if resolved.is_empty() {
+        RangeDecision::Unsatisfiable
+    } else {
+        RangeDecision::Partial(resolved)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Url;
+
+    fn request(range: Option<&str>) -> Request {
+        request_with_method(Method::Get, range)
+    }
+
+    fn request_with_method(method: Method, range: Option<&str>) -> Request {
+        let mut req = Request::new(method, Url::parse("https://example.com").unwrap());
+        if let Some(range) = range {
+            req.insert_header("Range", range);
+        }
+        req
+    }
+
+    #[test]
+    fn evaluate() {
+        assert_eq!(super::evaluate(&request(None), 10), RangeDecision::Full);
+        assert_eq!(
+            super::evaluate(&request(Some("pages=1-2")), 10),
+            RangeDecision::Full
+        );
+        assert_eq!(
+            super::evaluate(&request(Some("bytes=a-")), 10),
+            RangeDecision::Full
+        );
+
+        let decision = super::evaluate(&request(Some("bytes=0-4,-2,5-")), 10);
+        let mut expected = ByteRanges::new();
+        expected.push(0, 4);
+        expected.push(8, 9);
+        expected.push(5, 9);
+        assert_eq!(decision, RangeDecision::Partial(expected));
+
+        let decision = super::evaluate(&request(Some("bytes=10-,20-30")), 10);
+        assert_eq!(decision, RangeDecision::Unsatisfiable);
+        let decision = super::evaluate(&request(Some("bytes=0-")), 0);
+        assert_eq!(decision, RangeDecision::Unsatisfiable);
+    }
+
+    #[test]
+    fn zero_length_suffix() {
+        let decision = super::evaluate(&request(Some("bytes=-0")), 10);
+        assert_eq!(decision, RangeDecision::Unsatisfiable);
+
+        let decision = super::evaluate(&request(Some("bytes=0-4,-0")), 10);
+        let mut expected = ByteRanges::new();
+        expected.push(0, 4);
+        assert_eq!(decision, RangeDecision::Partial(expected));
+    }
+
+    #[test]
+    fn other_methods() {
+        let req = request_with_method(Method::Post, Some("bytes=0-4"));
+        assert_eq!(super::evaluate(&req, 10), RangeDecision::Full);
+        let req = request_with_method(Method::Put, Some("bytes=20-"));
+        assert_eq!(super::evaluate(&req, 10), RangeDecision::Full);
+    }
+
+    #[test]
+    fn garbage_versus_out_of_bounds() {
+        let decision = super::evaluate(&request(Some("bytes=garbage")), 10);
+        assert_eq!(decision.status(), StatusCode::Ok);
+        let decision = super::evaluate(&request(Some("bytes=20-")), 10);
+        assert_eq!(decision.status(), StatusCode::RequestedRangeNotSatisfiable);
+    }
+
+    #[test]
+    fn head() {
+        let req = request_with_method(Method::Head, Some("bytes=0-4,-2"));
+        let decision = super::evaluate(&req, 10);
+        assert_eq!(decision.status(), StatusCode::PartialContent);
+        assert_eq!(decision.content_range(10), None);
+        assert!(!decision.send_body(&req));
+
+        let req = request_with_method(Method::Head, Some("bytes=0-4"));
+        let decision = super::evaluate(&req, 10);
+        let content_range = decision.content_range(10).unwrap();
+        assert_eq!(content_range.to_string(), "bytes 0-4/10");
+        assert!(!decision.send_body(&req));
+
+        let req = request_with_method(Method::Head, Some("bytes=20-"));
+        let decision = super::evaluate(&req, 10);
+        assert_eq!(decision.status(), StatusCode::RequestedRangeNotSatisfiable);
+        assert_eq!(
+            decision.content_range(10).unwrap().to_string(),
+            "bytes */10"
+        );
+
+        let req = request(Some("bytes=0-4"));
+        let decision = super::evaluate(&req, 10);
+        assert!(decision.send_body(&req));
+        let req = request(None);
+        let decision = super::evaluate(&req, 10);
+        assert_eq!(decision.status(), StatusCode::Ok);
+        assert_eq!(decision.content_range(10), None);
+        assert!(decision.send_body(&req));
+    }
+}
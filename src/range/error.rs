@@ -0,0 +1,123 @@
+use crate::{Error, StatusCode};
+
+use std::fmt::{self, Display};
+
+/// An error produced while parsing or resolving ranges.
+///
+/// Errors returned by the range parsers wrap a `RangeError`, which can be
+/// retrieved using [`Error::downcast_ref`](crate::Error::downcast_ref).
+///
+/// # Examples
+///
+/// ```
+/// use http_types::range::{ByteRanges, RangeError};
+///
+/// let err = "bytes=5-1".parse::<ByteRanges>().unwrap_err();
+/// assert_eq!(err.downcast_ref::<RangeError>(), Some(&RangeError::Reversed));
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeError {
+    /// The range is syntactically invalid.
+    Malformed(&'static str),
+    /// The range start is greater than the range end.
+    Reversed,
+    /// The range can not be satisfied for the document size.
+    Unsatisfiable(&'static str),
+    /// Too many ranges were requested.
+    TooManyRanges,
+    /// The ranges request more bytes in total than allowed.
+    TooLarge,
+    /// The range unit is not supported.
+    InvalidUnit,
+    /// The ranges built by the server are not ascending and
+    /// non-overlapping.
+    Unordered,
+    /// The length of the body sent by the server does not match its
+    /// `Content-Range`.
+    BodyLengthMismatch,
+}
+
+impl RangeError {
+    /// Get the status code associated with this error.
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::Unsatisfiable(_) => StatusCode::RequestedRangeNotSatisfiable,
+            Self::Unordered | Self::BodyLengthMismatch => StatusCode::InternalServerError,
+            _ => StatusCode::BadRequest,
+        }
+    }
+
+    /// Convert into an `Error` using the associated status code.
+    ///
+    /// Prefer this over the `?` operator, which converts any error into a
+    /// `500 Internal Server Error`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::range::RangeError;
+    /// use http_types::StatusCode;
+    ///
+    /// fn check_total(total: u64) -> http_types::Result<()> {
+    ///     if total > 1024 {
+    ///         return Err(RangeError::TooLarge.into_error());
+    ///     }
+    ///     Ok(())
+    /// }
+    ///
+    /// let err = check_total(2048).unwrap_err();
+    /// assert_eq!(err.status(), StatusCode::BadRequest);
+    /// assert_eq!(err.downcast_ref::<RangeError>(), Some(&RangeError::TooLarge));
+    /// ```
+    pub fn into_error(self) -> Error {
+        let status = self.status();
+        self.with_status(status)
+    }
+
+    /// Convert into an `Error` using the given status code.
+    pub(crate) fn with_status(self, status: StatusCode) -> Error {
+        Error::new(status, self)
+    }
+}
+
+impl Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(msg) | Self::Unsatisfiable(msg) => write!(f, "{}", msg),
+            Self::Reversed => write!(f, "Invalid range: start is greater than end"),
+            Self::TooManyRanges => write!(f, "Too many ranges requested"),
+            Self::TooLarge => write!(f, "Requested ranges exceed the allowed total length"),
+            Self::InvalidUnit => write!(f, "Unsupported range unit"),
+            Self::Unordered => write!(
+                f,
+                "Invalid ranges: ranges must be ascending and non-overlapping"
+            ),
+            Self::BodyLengthMismatch => {
+                write!(f, "Content-Range length does not match the body length")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn into_error() {
+        let err = RangeError::TooLarge.into_error();
+        assert_eq!(err.status(), StatusCode::BadRequest);
+        let err = RangeError::BodyLengthMismatch.into_error();
+        assert_eq!(err.status(), StatusCode::InternalServerError);
+        let err = RangeError::Unsatisfiable("document is empty").into_error();
+        assert_eq!(err.status(), StatusCode::RequestedRangeNotSatisfiable);
+        assert_eq!(err.to_string(), "document is empty");
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Unsatisfiable("document is empty"))
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use crate::headers::CONTENT_RANGE;
+use crate::range::{AcceptRanges, ByteContentRange, ByteRanges};
+use crate::{Request, Response, StatusCode};
+
+/// Range request methods for `Request`.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::{Method, Request, Url};
+/// use http_types::range::{ByteRange, RangeRequestExt};
+///
+/// let mut req = Request::new(Method::Get, Url::parse("https://example.com")?);
+/// req.insert_header("Range", "bytes=0-499");
+///
+/// let ranges = req.byte_ranges()?.unwrap();
+/// assert_eq!(ranges.first(), Some(ByteRange::new(0, 499)));
+/// #
+/// # Ok(()) }
+/// ```
+pub trait RangeRequestExt {
+    /// Get the `Range` header.
+    ///
+    /// See [`ByteRanges::from_headers`] for how other range units are
+    /// handled.
+    fn byte_ranges(&self) -> crate::Result<Option<ByteRanges>>;
+}
+
+impl RangeRequestExt for Request {
+    fn byte_ranges(&self) -> crate::Result<Option<ByteRanges>> {
+        ByteRanges::from_headers(self)
+    }
+}
+
+/// Range response methods for `Response`.
+///
+/// # Examples
+///
+/// ```
+/// use http_types::Response;
+/// use http_types::range::{AcceptRanges, ByteContentRange, RangeResponseExt};
+///
+/// let mut res = Response::new(206);
+/// res.set_accept_ranges(AcceptRanges::with_bytes());
+/// res.set_content_range(ByteContentRange::new().with_range(0, 499).with_size(1000));
+///
+/// assert_eq!(res["Accept-Ranges"], "bytes");
+/// assert_eq!(res["Content-Range"], "bytes 0-499/1000");
+/// ```
+pub trait RangeResponseExt {
+    /// Set the `Content-Range` header.
+    fn set_content_range(&mut self, content_range: ByteContentRange);
+
+    /// Set the `Accept-Ranges` header.
+    fn set_accept_ranges(&mut self, accept_ranges: AcceptRanges);
+}
+
+impl RangeResponseExt for Response {
+    fn set_content_range(&mut self, content_range: ByteContentRange) {
+        content_range.apply(self);
+    }
+
+    fn set_accept_ranges(&mut self, accept_ranges: AcceptRanges) {
+        accept_ranges.apply(self);
+    }
+}
+
+/// Degrade a range response into a full `200 OK` response.
+///
+/// A server ignoring a range request, for example because the `If-Range`
+/// validator did not match, must send the full representation with a `200 OK`
+/// status and no `Content-Range` header. This removes any `Content-Range`
+/// header and sets the status to `200 OK`; the body is left untouched.
+///
+/// # Examples
+///
+/// ```
+/// use http_types::{Response, StatusCode};
+/// use http_types::range::{self, ByteContentRange, RangeResponseExt};
+///
+/// let mut res = Response::new(206);
+/// res.set_content_range(ByteContentRange::new().with_range(0, 499).with_size(1000));
+///
+/// range::full_response(&mut res);
+/// assert_eq!(res.status(), StatusCode::Ok);
+/// assert!(res.header("Content-Range").is_none());
+/// ```
+pub fn full_response(res: &mut Response) {
+    res.remove_header(CONTENT_RANGE);
+    res.set_status(StatusCode::Ok);
+}
+
+/// Create a `416 Requested Range Not Satisfiable` response for a document of
+/// the given size.
+///
+/// The response carries a `Content-Range` header listing the complete size of
+/// the document, and an empty body.
+///
+/// # Examples
+///
+/// ```
+/// use http_types::StatusCode;
+/// use http_types::range;
+///
+/// let res = range::range_not_satisfiable(1000);
+/// assert_eq!(res.status(), StatusCode::RequestedRangeNotSatisfiable);
+/// assert_eq!(res["Content-Range"], "bytes */1000");
+/// ```
+pub fn range_not_satisfiable(size: u64) -> Response {
+    let mut res = Response::new(StatusCode::RequestedRangeNotSatisfiable);
+    res.set_content_range(ByteContentRange::unsatisfied(size));
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::range::{ByteRange, RangeError};
+    use crate::{Method, Url};
+
+    fn request(range: Option<&str>) -> Request {
+        let mut req = Request::new(Method::Get, Url::parse("https://example.com").unwrap());
+        if let Some(range) = range {
+            req.insert_header("Range", range);
+        }
+        req
+    }
+
+    #[test]
+    fn byte_ranges() -> crate::Result<()> {
+        assert_eq!(request(None).byte_ranges()?, None);
+
+        let ranges = request(Some("bytes=0-4,-2")).byte_ranges()?.unwrap();
+        assert_eq!(
+            ranges.as_slice(),
+            &[ByteRange::new(0, 4), ByteRange::new(None, 2)]
+        );
+
+        let err = request(Some("bytes=0-4,,5-9")).byte_ranges().unwrap_err();
+        assert_eq!(err.status(), 400);
+        let err = request(Some("bytes=5-1")).byte_ranges().unwrap_err();
+        assert_eq!(err.status(), 400);
+        assert_eq!(
+            err.downcast_ref::<RangeError>(),
+            Some(&RangeError::Reversed)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn set_headers() {
+        let mut res = Response::new(StatusCode::PartialContent);
+        res.set_content_range(ByteContentRange::new().with_range(0, 4).with_size(10));
+        res.set_accept_ranges(AcceptRanges::with_bytes());
+        assert_eq!(res["Content-Range"], "bytes 0-4/10");
+        assert_eq!(res["Accept-Ranges"], "bytes");
+
+        res.set_content_range(ByteContentRange::new().with_range(5, 9).with_size(10));
+        res.set_accept_ranges(AcceptRanges::new());
+        assert_eq!(res["Content-Range"], "bytes 5-9/10");
+        assert_eq!(res["Accept-Ranges"], "none");
+    }
+
+    #[test]
+    fn full_response() {
+        let mut res = Response::new(StatusCode::PartialContent);
+        res.set_accept_ranges(AcceptRanges::with_bytes());
+        res.set_content_range(ByteContentRange::new().with_range(0, 4).with_size(10));
+
+        super::full_response(&mut res);
+        assert_eq!(res.status(), StatusCode::Ok);
+        assert!(res.header("Content-Range").is_none());
+        // Range support is still advertised on the full response.
+        assert_eq!(res["Accept-Ranges"], "bytes");
+
+        // Degrading a response without any range header is a no-op.
+        let mut res = Response::new(StatusCode::Ok);
+        super::full_response(&mut res);
+        assert_eq!(res.status(), StatusCode::Ok);
+        assert!(res.header("Content-Range").is_none());
+    }
+
+    #[test]
+    fn range_not_satisfiable() {
+        let res = super::range_not_satisfiable(10);
+        assert_eq!(res.status(), StatusCode::RequestedRangeNotSatisfiable);
+        assert_eq!(res["Content-Range"], "bytes */10");
+        assert_eq!(res.len(), Some(0));
+
+        let res = super::range_not_satisfiable(0);
+        assert_eq!(res["Content-Range"], "bytes */0");
+    }
+}
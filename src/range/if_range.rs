@@ -0,0 +1,249 @@
+use crate::conditional::ETag;
+use crate::headers::{HeaderName, HeaderValue, Headers, ToHeaderValues, IF_RANGE};
+use crate::utils::{fmt_http_date, parse_http_date, HttpDate};
+
+use std::fmt::{self, Display};
+use std::option;
+use std::time::SystemTime;
+
+/// Make a range request conditional on the representation being unchanged.
+///
+/// The validator is either an entity tag or an HTTP-date. The range is only
+/// honored if the validator matches the current representation; otherwise the
+/// full representation should be sent.
+///
+/// # Specifications
+///
+/// - [RFC 7233, section 3.2: If-Range](https://tools.ietf.org/html/rfc7233#section-3.2)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Request;
+/// use http_types::range::IfRange;
+/// use std::time::{Duration, SystemTime};
+///
+/// let date = SystemTime::now();
+/// let if_range = IfRange::Date(date);
+///
+/// let mut req = Request::get("https://example.com");
+/// if_range.apply(&mut req);
+///
+/// let if_range = IfRange::from_headers(req)?.unwrap();
+/// assert!(if_range.matches_last_modified(date - Duration::from_secs(60)));
+/// assert!(!if_range.matches_last_modified(date + Duration::from_secs(60)));
+/// #
+/// # Ok(()) }
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfRange {
+    /// An entity tag validator.
+    ETag(ETag),
+    /// An HTTP-date validator.
+    Date(SystemTime),
+}
+
+impl IfRange {
+    /// Create a new instance from headers.
+    ///
+    /// If multiple `If-Range` headers are found the last one is used.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(IF_RANGE) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        // If a header is returned we can assume at least one exists.
+        let s = headers.iter().last().unwrap().as_str();
+        Self::from_str(s).map(Some)
+    }
+
+    /// Sets the `If-Range` header.
+    pub fn apply(&self, mut headers: impl AsMut<Headers>) {
+        headers.as_mut().insert(IF_RANGE, self.value());
+    }
+
+    /// Get the `HeaderName`.
+    pub fn name(&self) -> HeaderName {
+        IF_RANGE
+    }
+
+    /// Get the `HeaderValue`.
+    pub fn value(&self) -> HeaderValue {
+        let s = self.to_string();
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(s.into()) }
+    }
+
+    /// Returns the entity tag validator, if any.
+    pub fn etag(&self) -> Option<&ETag> {
+        match self {
+            Self::ETag(etag) => Some(etag),
+            Self::Date(_) => None,
+        }
+    }
+
+    /// Returns the date validator, if any.
+    pub fn date(&self) -> Option<SystemTime> {
+        match self {
+            Self::ETag(_) => None,
+            Self::Date(date) => Some(*date),
+        }
+    }
+
+    /// Returns `true` if the range should be honored for a representation
+    /// last modified at the given time.
+    ///
+    /// The range is honored only if the representation has not been modified
+    /// since the validator date. Both times are compared with the
+    /// second-precision of HTTP-dates. Always returns `false` for an entity
+    /// tag validator.
+    pub fn matches_last_modified(&self, last_modified: SystemTime) -> bool {
+        match self {
+            Self::ETag(_) => false,
+            Self::Date(date) => HttpDate::from(last_modified) <= HttpDate::from(*date),
+        }
+    }
+
+    /// Returns `true` if the range should be honored for a representation
+    /// with the given entity tag.
+    ///
+    /// `If-Range` requires the strong comparison function: both entity tags
+    /// must be strong and their opaque tags must match byte for byte.
+    /// Always returns `false` for a date validator.
+    ///
+    /// # Specifications
+    ///
+    /// - [RFC 7232, section 2.3.2: Comparison](https://tools.ietf.org/html/rfc7232#section-2.3.2)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::conditional::ETag;
+    /// use http_types::range::IfRange;
+    ///
+    /// let if_range = IfRange::ETag(ETag::new("xyzzy".into()));
+    /// assert!(if_range.matches_strong(&ETag::new("xyzzy".into())));
+    /// assert!(!if_range.matches_strong(&ETag::new_weak("xyzzy".into())));
+    /// ```
+    pub fn matches_strong(&self, other: &ETag) -> bool {
+        match (self, other) {
+            (Self::ETag(ETag::Strong(tag)), ETag::Strong(other)) => tag == other,
+            _ => false,
+        }
+    }
+
+    /// Parse a string into an `IfRange`.
+    ///
+    /// Values starting with `"` or `W/` are parsed as an entity tag, anything
+    /// else as an HTTP-date. Entity tags have no escaping mechanism, so an
+    /// opaque tag containing a `"` is rejected.
+    pub(crate) fn from_str(s: &str) -> crate::Result<Self> {
+        let s = s.trim();
+        if s.starts_with('"') || s.starts_with("W/") {
+            ETag::from_str(s).map(Self::ETag)
+        } else {
+            parse_http_date(s).map(Self::Date)
+        }
+    }
+}
+
+impl Display for IfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ETag(etag) => write!(f, "{}", etag),
+            Self::Date(date) => write!(f, "{}", fmt_http_date(*date)),
+        }
+    }
+}
+
+impl ToHeaderValues for IfRange {
+    type Iter = option::IntoIter<HeaderValue>;
+    fn to_header_values(&self) -> crate::Result<Self::Iter> {
+        self.value().to_header_values()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headers::Headers;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn smoke() -> crate::Result<()> {
+        let if_range = IfRange::ETag(ETag::new(String::from("xyzzy")));
+
+        let mut headers = Headers::new();
+        if_range.apply(&mut headers);
+
+        assert_eq!(headers[IF_RANGE], r#""xyzzy""#);
+        let if_range = IfRange::from_headers(headers)?.unwrap();
+        assert_eq!(if_range.etag(), Some(&ETag::new(String::from("xyzzy"))));
+        assert_eq!(if_range.date(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn date() -> crate::Result<()> {
+        let mut headers = Headers::new();
+        headers.insert(IF_RANGE, "Wed, 21 Oct 2015 07:28:00 GMT");
+
+        let if_range = IfRange::from_headers(headers)?.unwrap();
+        let date = UNIX_EPOCH + Duration::from_secs(1_445_412_480);
+        assert_eq!(if_range.date(), Some(date));
+        assert_eq!(if_range.to_string(), "Wed, 21 Oct 2015 07:28:00 GMT");
+        Ok(())
+    }
+
+    #[test]
+    fn matches_last_modified() {
+        let date = UNIX_EPOCH + Duration::from_secs(1_445_412_480);
+        let if_range = IfRange::Date(date);
+
+        // Equal to the validator date.
+        assert!(if_range.matches_last_modified(date));
+        assert!(if_range.matches_last_modified(date + Duration::from_millis(500)));
+        // Modified before the validator date.
+        assert!(if_range.matches_last_modified(date - Duration::from_secs(1)));
+        // Modified after the validator date.
+        assert!(!if_range.matches_last_modified(date + Duration::from_secs(1)));
+
+        let if_range = IfRange::ETag(ETag::new(String::from("xyzzy")));
+        assert!(!if_range.matches_last_modified(date));
+    }
+
+    #[test]
+    fn matches_strong() -> crate::Result<()> {
+        let strong = ETag::new(String::from("x"));
+        let weak = ETag::new_weak(String::from("x"));
+
+        let if_range = IfRange::from_str(r#""x""#)?;
+        assert!(if_range.matches_strong(&strong));
+        assert!(!if_range.matches_strong(&weak));
+        assert!(!if_range.matches_strong(&ETag::new(String::from("y"))));
+
+        let if_range = IfRange::from_str(r#"W/"x""#)?;
+        assert_eq!(if_range.etag(), Some(&weak));
+        assert!(!if_range.matches_strong(&strong));
+        assert!(!if_range.matches_strong(&weak));
+
+        let if_range = IfRange::Date(UNIX_EPOCH);
+        assert!(!if_range.matches_strong(&strong));
+
+        assert!(IfRange::from_str(r#""a\"b""#).is_err());
+        assert!(IfRange::from_str(r#""a"b""#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn bad_request_on_parse_error() {
+        let mut headers = Headers::new();
+        headers.insert(IF_RANGE, "<nori ate the tag. yum.>");
+        let err = IfRange::from_headers(headers).unwrap_err();
+        assert_eq!(err.status(), 400);
+    }
+}
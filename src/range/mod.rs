@@ -0,0 +1,64 @@
+//! HTTP range requests.
+//!
+//! Range requests allow a client to ask a server for only a portion of a
+//! representation. This is commonly used to resume interrupted downloads, or
+//! to split a large download over multiple connections.
+//!
+//! Only the `bytes` range unit is interpreted by this module. Other range
+//! units are represented as [`Unit::Other`] and must be processed manually.
+//!
+//! # Errors
+//!
+//! Errors produced by this module wrap a [`RangeError`], and use the status
+//! code returned by [`RangeError::status`]:
+//!
+//! - Syntax errors, such as a malformed bound or an unsupported unit, use
+//!   `400 Bad Request`.
+//! - Ranges which can not be satisfied, such as a range starting past the end
+//!   of the document or a zero-length suffix range, use
+//!   `416 Requested Range Not Satisfiable`.
+//! - Mistakes made by the server while building a response, such as ranges
+//!   which are not ascending or a body length not matching the
+//!   `Content-Range`, use `500 Internal Server Error`. Invalid values supplied
+//!   by the server, such as a multipart boundary or a custom unit which can
+//!   not be serialized, wrap a [`RangeError::Malformed`] but use
+//!   `500 Internal Server Error` as well.
+//!
+//! Frameworks preferring a different mapping can retrieve the `RangeError`
+//! using [`Error::downcast_ref`](crate::Error::downcast_ref) and choose their
+//! own status code.
+//!
+//! # Further Reading
+//!
+//! - [MDN: HTTP Range Requests](https://developer.mozilla.org/en-US/docs/Web/HTTP/Range_requests)
+
+pub mod byte_ranges;
+
+mod accept_ranges;
+mod byte_content_range;
+mod decision;
+mod error;
+mod ext;
+mod if_range;
+mod multipart_byte_ranges;
+mod resolved_range;
+mod sequential_reader;
+mod server_range_support;
+mod strict_opts;
+mod unit;
+
+pub use accept_ranges::AcceptRanges;
+pub use byte_content_range::ByteContentRange;
+pub use decision::{evaluate, RangeDecision};
+pub use error::RangeError;
+pub use ext::{full_response, range_not_satisfiable, RangeRequestExt, RangeResponseExt};
+pub use if_range::IfRange;
+pub use multipart_byte_ranges::MultipartByteRanges;
+pub use resolved_range::ResolvedRange;
+pub use sequential_reader::SequentialReader;
+pub use server_range_support::ServerRangeSupport;
+pub use strict_opts::StrictOpts;
+pub use unit::Unit;
+
+#[doc(inline)]
+pub use byte_ranges::{ByteRange, ByteRanges};
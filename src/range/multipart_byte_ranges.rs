@@ -0,0 +1,230 @@
+use crate::parse_utils::is_token;
+use crate::range::{ByteContentRange, ByteRanges, RangeError};
+use crate::StatusCode;
+
+/// The framing of a `multipart/byteranges` response body.
+///
+/// A multiple range response carries each range in its own body part,
+/// preceded by a boundary delimiter and the part headers. The body is laid
+/// out as follows, where the first part is not preceded by a CRLF:
+///
+/// ```text
+/// \r\n--{boundary}\r\n
+/// Content-Type: {content type}\r\n
+/// Content-Range: bytes {start}-{end}/{size}\r\n
+/// \r\n
+/// {range bytes}
+/// ...
+/// \r\n--{boundary}--\r\n
+/// ```
+///
+/// Use [`prefix`](#method.prefix) and [`epilogue`](#method.epilogue) to write
+/// the body, and [`content_length`](#method.content_length) to get its
+/// length.
+///
+/// # Specifications
+///
+/// - [RFC 7233, appendix A: Internet Media Type multipart/byteranges](https://tools.ietf.org/html/rfc7233#appendix-A)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::range::{ByteRanges, MultipartByteRanges};
+///
+/// let document = b"hello world";
+/// let ranges: ByteRanges = "bytes=0-4,-5".parse()?;
+/// let multipart = MultipartByteRanges::new(&ranges, 11, "text/plain", "XYZ")?;
+/// assert_eq!(multipart.content_type(), "multipart/byteranges; boundary=XYZ");
+///
+/// let mut body = Vec::new();
+/// for (index, content_range) in multipart.parts().enumerate() {
+///     let (start, end) = content_range.range().unwrap();
+///     body.extend_from_slice(multipart.prefix(index).as_bytes());
+///     body.extend_from_slice(&document[start as usize..=end as usize]);
+/// }
+/// body.extend_from_slice(multipart.epilogue().as_bytes());
+/// assert_eq!(multipart.content_length(), Some(body.len() as u64));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultipartByteRanges {
+    boundary: String,
+    parts: Vec<(ByteContentRange, String)>,
+}
+
+impl MultipartByteRanges {
+    /// Create a new instance for the ranges of a document of the given size
+    /// and content type.
+    ///
+    /// Parts are laid out in the order of the ranges. Returns a `416
+    /// Requested Range Not Satisfiable` error if any of the ranges is not
+    /// satisfiable, and a `500 Internal Server Error` if the content type or
+    /// the boundary is invalid.
+    ///
+    /// Per [RFC 2046, section
+    /// 5.1.1](https://tools.ietf.org/html/rfc2046#section-5.1.1), the boundary
+    /// is 1 to 70 characters among digits, letters, space and `'()+_,-./:=?`,
+    /// and must not end with a space. It must not appear in the document.
+    pub fn new(
+        ranges: &ByteRanges,
+        size: u64,
+        content_type: &str,
+        boundary: impl Into<String>,
+    ) -> crate::Result<Self> {
+        let boundary = boundary.into();
+        if !is_valid_boundary(&boundary) {
+            return Err(RangeError::Malformed("Invalid multipart boundary")
+                .with_status(StatusCode::InternalServerError));
+        }
+
+        Ok(Self {
+            boundary,
+            parts: ranges.parts(size, content_type)?,
+        })
+    }
+
+    /// Returns the boundary delimiting the parts.
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// Returns the `Content-Type` of the response.
+    ///
+    /// The boundary is written as a quoted string if it is not a token, such
+    /// as a boundary containing a space or a `:`.
+    pub fn content_type(&self) -> String {
+        if is_token(&self.boundary) {
+            format!("multipart/byteranges; boundary={}", self.boundary)
+        } else {
+            // Boundaries never contain `"` or `\`, so no escaping is needed.
+            format!("multipart/byteranges; boundary=\"{}\"", self.boundary)
+        }
+    }
+
+    /// Returns the `Content-Range` of each part.
+    pub fn parts(&self) -> impl Iterator<Item = ByteContentRange> + '_ {
+        self.parts.iter().map(|(range, _)| *range)
+    }
+
+    /// Returns the boundary delimiter and headers written before the bytes of
+    /// the part at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn prefix(&self, index: usize) -> String {
+        let crlf = if index == 0 { "" } else { "\r\n" };
+        format!("{}--{}\r\n{}", crlf, self.boundary, self.parts[index].1)
+    }
+
+    /// Returns the closing boundary delimiter written after the last part.
+    pub fn epilogue(&self) -> String {
+        format!("\r\n--{}--\r\n", self.boundary)
+    }
+
+    /// Returns the `Content-Length` of the response: the length of the whole
+    /// body, including boundaries, part headers and range bytes.
+    ///
+    /// Returns `None` if the length overflows.
+    pub fn content_length(&self) -> Option<u64> {
+        let mut len = self.epilogue().len() as u64;
+        for (index, (content_range, _)) in self.parts.iter().enumerate() {
+            len = len
+                .checked_add(self.prefix(index).len() as u64)?
+                .checked_add(content_range.content_length()?)?;
+        }
+        Some(len)
+    }
+}
+
+/// Returns `true` if the boundary matches the RFC 2046 `boundary` grammar.
+fn is_valid_boundary(boundary: &str) -> bool {
+    let is_bchar = |b: u8| b.is_ascii_alphanumeric() || b"'()+_,-./:=? ".contains(&b);
+    (1..=70).contains(&boundary.len()) && !boundary.ends_with(' ') && boundary.bytes().all(is_bchar)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Mime;
+    use std::str::FromStr;
+
+    #[test]
+    fn content_length() -> crate::Result<()> {
+        let ranges: ByteRanges = "bytes=0-1,8-9".parse()?;
+        let multipart = MultipartByteRanges::new(&ranges, 10, "text/plain", "b")?;
+        assert_eq!(multipart.boundary(), "b");
+
+        let body = format!(
+            "{}01{}89{}",
+            multipart.prefix(0),
+            multipart.prefix(1),
+            multipart.epilogue()
+        );
+        assert_eq!(
+            body,
+            "--b\r\nContent-Type: text/plain\r\nContent-Range: bytes 0-1/10\r\n\r\n01\
+             \r\n--b\r\nContent-Type: text/plain\r\nContent-Range: bytes 8-9/10\r\n\r\n89\
+             \r\n--b--\r\n"
+        );
+        assert_eq!(multipart.content_length(), Some(body.len() as u64));
+        Ok(())
+    }
+
+    #[test]
+    fn unsatisfiable() -> crate::Result<()> {
+        let ranges: ByteRanges = "bytes=0-1,20-".parse()?;
+        let err = MultipartByteRanges::new(&ranges, 10, "text/plain", "b").unwrap_err();
+        assert_eq!(err.status(), 416);
+        Ok(())
+    }
+
+    #[test]
+    fn boundary() -> crate::Result<()> {
+        let ranges: ByteRanges = "bytes=0-1,8-9".parse()?;
+        let long = "a".repeat(70);
+        for boundary in &["b", "gc0p4Jq0M2Yt08jU534c0p", "a'()+_,-./:=? z", &long] {
+            assert!(MultipartByteRanges::new(&ranges, 10, "text/plain", *boundary).is_ok());
+        }
+
+        let too_long = "a".repeat(71);
+        for boundary in &["", "b ", "b\r\nEvil: x", "b;c", "b\"", "é", &too_long] {
+            let err = MultipartByteRanges::new(&ranges, 10, "text/plain", *boundary).unwrap_err();
+            assert_eq!(err.status(), 500);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn content_type() -> crate::Result<()> {
+        let ranges: ByteRanges = "bytes=0-1,8-9".parse()?;
+        for (boundary, expected) in &[
+            ("XYZ", "multipart/byteranges; boundary=XYZ"),
+            ("a b:c", "multipart/byteranges; boundary=\"a b:c\""),
+            (
+                "a'()+_,-./:=? z",
+                "multipart/byteranges; boundary=\"a'()+_,-./:=? z\"",
+            ),
+        ] {
+            let multipart = MultipartByteRanges::new(&ranges, 10, "text/plain", *boundary)?;
+            let content_type = multipart.content_type();
+            assert_eq!(content_type, *expected);
+
+            let mime = Mime::from_str(&content_type)?;
+            assert_eq!(mime.essence(), "multipart/byteranges");
+            assert_eq!(mime.param("boundary").unwrap(), *boundary);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_content_type() -> crate::Result<()> {
+        let ranges: ByteRanges = "bytes=0-1,8-9".parse()?;
+        let err = MultipartByteRanges::new(&ranges, 10, "text/plain\r\nEvil: x", "b").unwrap_err();
+        assert_eq!(err.status(), 500);
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+use crate::range::ByteRange;
+
+use std::fmt::{self, Display};
+use std::ops::{Bound, RangeBounds};
+
+/// A range resolved against a document size, along with the range as it was
+/// originally requested.
+///
+/// This is produced by [`ByteRange::resolve_tracked`], and allows logging the
+/// range the client sent while serving its concrete bounds.
+///
+/// `ResolvedRange` implements `RangeBounds<u64>`, so it can be passed to APIs
+/// such as slicing helpers. `ByteRange` itself does not: suffix and
+/// open-ended ranges only have concrete bounds once the document size is
+/// known.
+///
+/// # Examples
+///
+/// ```
+/// use http_types::range::ByteRange;
+///
+/// let resolved = ByteRange::new(None, 5).resolve_tracked(10).unwrap();
+/// assert_eq!(resolved.requested(), ByteRange::new(None, 5));
+/// assert_eq!(resolved.bounds(), (5, 9));
+/// assert_eq!(resolved.to_string(), "-5 (5-9)");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResolvedRange {
+    requested: ByteRange,
+    start: u64,
+    end: u64,
+}
+
+impl ResolvedRange {
+    /// Create a new instance.
+    pub(crate) fn new(requested: ByteRange, start: u64, end: u64) -> Self {
+        Self {
+            requested,
+            start,
+            end,
+        }
+    }
+
+    /// Returns the range as it was originally requested.
+    pub fn requested(&self) -> ByteRange {
+        self.requested
+    }
+
+    /// Returns the first byte of the resolved range.
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns the last byte of the resolved range.
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+
+    /// Returns the inclusive `(start, end)` bounds of the resolved range.
+    pub fn bounds(&self) -> (u64, u64) {
+        (self.start, self.end)
+    }
+}
+
+impl RangeBounds<u64> for ResolvedRange {
+    fn start_bound(&self) -> Bound<&u64> {
+        Bound::Included(&self.start)
+    }
+
+    fn end_bound(&self) -> Bound<&u64> {
+        Bound::Included(&self.end)
+    }
+}
+
+impl Display for ResolvedRange {
+    /// Format the requested range, followed by the resolved bounds in
+    /// parentheses.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}-{})", self.requested, self.start, self.end)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resolve_tracked() {
+        let resolved = ByteRange::new(90, None).resolve_tracked(100).unwrap();
+        assert_eq!(resolved.requested(), ByteRange::new(90, None));
+        assert_eq!(resolved.start(), 90);
+        assert_eq!(resolved.end(), 99);
+        assert_eq!(resolved.to_string(), "90- (90-99)");
+
+        assert_eq!(ByteRange::new(100, None).resolve_tracked(100), None);
+    }
+
+    #[test]
+    fn range_bounds() {
+        let resolved = ByteRange::new(None, 3).resolve_tracked(10).unwrap();
+        assert_eq!(resolved.start_bound(), Bound::Included(&7));
+        assert_eq!(resolved.end_bound(), Bound::Included(&9));
+        assert!(resolved.contains(&7));
+        assert!(!resolved.contains(&6));
+
+        fn collect(range: impl RangeBounds<u64>) -> Vec<u64> {
+            (0..10).filter(|n| range.contains(n)).collect()
+        }
+        assert_eq!(collect(resolved), vec![7, 8, 9]);
+    }
+}
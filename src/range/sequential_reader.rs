@@ -0,0 +1,195 @@
+use crate::range::{ByteRanges, RangeError};
+
+use futures_lite::{io, prelude::*};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::vec;
+
+/// A reader yielding the bytes of multiple ranges from a forward-only source.
+///
+/// The source is read once, from the start of the document. Calling
+/// [`next_range`](#method.next_range) discards the rest of the current range
+/// and the gap before the next one, after which reading yields exactly the
+/// bytes of that range. This allows serving multiple ranges from sources
+/// which can not seek, such as a network stream.
+///
+/// This is created by [`ByteRanges::sequential_reader`].
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> { async_std::task::block_on(async {
+/// use futures_lite::io::{AsyncReadExt, Cursor};
+/// use http_types::range::ByteRanges;
+///
+/// let ranges: ByteRanges = "bytes=0-4,-5".parse()?;
+/// let document = Cursor::new(b"hello world".to_vec());
+/// let mut reader = ranges.sequential_reader(document, 11)?;
+///
+/// let mut parts = vec![];
+/// while let Some(bounds) = reader.next_range().await? {
+///     let mut buf = String::new();
+///     reader.read_to_string(&mut buf).await?;
+///     parts.push((bounds, buf));
+/// }
+/// assert_eq!(parts[0], ((0, 4), String::from("hello")));
+/// assert_eq!(parts[1], ((6, 10), String::from("world")));
+/// # Ok(()) }) }
+/// ```
+#[derive(Debug)]
+pub struct SequentialReader<R> {
+    reader: R,
+    bounds: vec::IntoIter<(u64, u64)>,
+    position: u64,
+    remaining: u64,
+}
+
+impl<R: AsyncRead + Unpin> SequentialReader<R> {
+    /// Create a new instance.
+    ///
+    /// Returns a `416 Requested Range Not Satisfiable` error if any of the
+    /// ranges can not be satisfied, and a `500 Internal Server Error` if the
+    /// ranges are not sorted and coalesced.
+    pub(crate) fn new(ranges: &ByteRanges, reader: R, size: u64) -> crate::Result<Self> {
+        let bounds = ranges.resolve(size)?;
+        let ordered = bounds.windows(2).all(|pair| pair[0].1 < pair[1].0);
+        if !ordered {
+            return Err(RangeError::Unordered.into_error());
+        }
+
+        Ok(Self {
+            reader,
+            bounds: bounds.into_iter(),
+            position: 0,
+            remaining: 0,
+        })
+    }
+
+    /// Advance to the next range, returning its inclusive `(start, end)`
+    /// bounds.
+    ///
+    /// Unread bytes of the current range and the bytes before the next range
+    /// are read from the source and discarded. Returns `None` once all ranges
+    /// have been visited.
+    pub async fn next_range(&mut self) -> crate::Result<Option<(u64, u64)>> {
+        let (start, end) = match self.bounds.next() {
+            Some(bounds) => bounds,
+            None => return Ok(None),
+        };
+
+        // Ranges are sorted, so the next range starts past the current one.
+        let skip = start - self.position;
+        let skipped = io::copy((&mut self.reader).take(skip), io::sink()).await?;
+        if skipped != skip {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+
+        self.position = start;
+        self.remaining = end - start + 1;
+        Ok(Some((start, end)))
+    }
+
+    /// Consume the reader, returning the underlying source.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for SequentialReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.remaining == 0 || buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let max = buf
+            .len()
+            .min(self.remaining.min(usize::MAX as u64) as usize);
+        let n = match Pin::new(&mut self.reader).poll_read(cx, &mut buf[..max]) {
+            Poll::Ready(Ok(n)) => n,
+            other => return other,
+        };
+        if n == 0 {
+            return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+        }
+
+        self.position += n as u64;
+        self.remaining -= n as u64;
+        Poll::Ready(Ok(n))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures_lite::io::Cursor;
+
+    #[async_std::test]
+    async fn sequential_reader() -> crate::Result<()> {
+        let ranges: ByteRanges = "bytes=0-1,4-5,-2".parse()?;
+        let document = Cursor::new(b"0123456789".to_vec());
+        let mut reader = ranges.sequential_reader(document, 10)?;
+
+        // Only read part of the first range.
+        assert_eq!(reader.next_range().await?, Some((0, 1)));
+        let mut buf = [0; 1];
+        reader.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"0");
+
+        assert_eq!(reader.next_range().await?, Some((4, 5)));
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf).await?;
+        assert_eq!(buf, "45");
+
+        assert_eq!(reader.next_range().await?, Some((8, 9)));
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf).await?;
+        assert_eq!(buf, "89");
+
+        assert_eq!(reader.next_range().await?, None);
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn truncated_source() -> crate::Result<()> {
+        let ranges: ByteRanges = "bytes=2-3,8-9".parse()?;
+        let document = Cursor::new(b"0123".to_vec());
+        let mut reader = ranges.sequential_reader(document, 10)?;
+
+        assert_eq!(reader.next_range().await?, Some((2, 3)));
+        assert!(reader.next_range().await.is_err());
+
+        let ranges: ByteRanges = "bytes=2-5".parse()?;
+        let document = Cursor::new(b"0123".to_vec());
+        let mut reader = ranges.sequential_reader(document, 10)?;
+        assert_eq!(reader.next_range().await?, Some((2, 5)));
+        let mut buf = vec![];
+        assert!(reader.read_to_end(&mut buf).await.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_ranges() -> crate::Result<()> {
+        let ranges: ByteRanges = "bytes=4-5,0-1".parse()?;
+        let err = ranges
+            .sequential_reader(Cursor::new(vec![]), 10)
+            .unwrap_err();
+        assert_eq!(err.status(), 500);
+
+        let ranges: ByteRanges = "bytes=0-4,4-5".parse()?;
+        let err = ranges
+            .sequential_reader(Cursor::new(vec![]), 10)
+            .unwrap_err();
+        assert_eq!(err.status(), 500);
+
+        let ranges: ByteRanges = "bytes=0-4,20-".parse()?;
+        let err = ranges
+            .sequential_reader(Cursor::new(vec![]), 10)
+            .unwrap_err();
+        assert_eq!(err.status(), 416);
+        Ok(())
+    }
+}
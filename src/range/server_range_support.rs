@@ -0,0 +1,82 @@
+use crate::range::{AcceptRanges, Unit};
+
+/// The range units a server can serve.
+///
+/// This describes the server capabilities declaratively, and maps them to
+/// the `Accept-Ranges` header to advertise.
+///
+/// # Examples
+///
+/// ```
+/// use http_types::range::{ServerRangeSupport, Unit};
+///
+/// let support = ServerRangeSupport {
+///     bytes: true,
+///     others: vec![Unit::other("pages")],
+/// };
+/// assert_eq!(support.into_accept_ranges().to_string(), "bytes, pages");
+///
+/// let support = ServerRangeSupport::default();
+/// assert_eq!(support.into_accept_ranges().to_string(), "none");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerRangeSupport {
+    /// Whether the `bytes` range unit is supported.
+    pub bytes: bool,
+    /// The custom range units supported.
+    pub others: Vec<Unit>,
+}
+
+impl ServerRangeSupport {
+    /// Convert into the `Accept-Ranges` header advertising the supported
+    /// units.
+    ///
+    /// The `bytes` unit is listed first. When no unit is supported, the
+    /// header advertises `none`.
+    pub fn into_accept_ranges(self) -> AcceptRanges {
+        let mut accept_ranges = AcceptRanges::new();
+        if self.bytes {
+            accept_ranges.add_unit(Unit::Bytes);
+        }
+        for unit in self.others {
+            accept_ranges.add_unit(unit);
+        }
+        accept_ranges
+    }
+}
+
+impl From<ServerRangeSupport> for AcceptRanges {
+    fn from(support: ServerRangeSupport) -> Self {
+        support.into_accept_ranges()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn into_accept_ranges() {
+        let support = ServerRangeSupport {
+            bytes: false,
+            others: vec![Unit::other("pages"), Unit::Bytes, Unit::other("pages")],
+        };
+        let accept_ranges = AcceptRanges::from(support);
+        assert_eq!(accept_ranges, "pages, bytes");
+
+        let support = ServerRangeSupport {
+            bytes: true,
+            others: vec![],
+        };
+        assert_eq!(support.into_accept_ranges(), AcceptRanges::with_bytes());
+        assert!(ServerRangeSupport::default().into_accept_ranges().is_none());
+
+        let support = ServerRangeSupport {
+            bytes: false,
+            others: vec![Unit::other("none"), Unit::other("a b")],
+        };
+        let accept_ranges = support.into_accept_ranges();
+        assert!(accept_ranges.is_none());
+        assert_eq!(accept_ranges, "none");
+    }
+}
@@ -0,0 +1,66 @@
+/// Options for [`ByteRanges::parse_strict`](crate::range::ByteRanges::parse_strict).
+///
+/// By default no limit applies, and suffix and overlapping ranges are
+/// allowed. Each option tightens one of the checks.
+///
+/// # Examples
+///
+/// ```
+/// use http_types::range::StrictOpts;
+///
+/// let opts = StrictOpts::new()
+///     .with_max_count(8)
+///     .with_max_total_len(1024)
+///     .allow_suffix(false)
+///     .allow_overlap(false);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrictOpts {
+    pub(crate) max_count: Option<usize>,
+    pub(crate) max_total_len: Option<u64>,
+    pub(crate) allow_suffix: bool,
+    pub(crate) allow_overlap: bool,
+}
+
+impl StrictOpts {
+    /// Create a new instance.
+    pub fn new() -> Self {
+        Self {
+            max_count: None,
+            max_total_len: None,
+            allow_suffix: true,
+            allow_overlap: true,
+        }
+    }
+
+    /// Reject sets with more than `max` ranges.
+    pub fn with_max_count(mut self, max: usize) -> Self {
+        self.max_count = Some(max);
+        self
+    }
+
+    /// Reject sets requesting more than `max` bytes in total, overlaps
+    /// included.
+    pub fn with_max_total_len(mut self, max: u64) -> Self {
+        self.max_total_len = Some(max);
+        self
+    }
+
+    /// Whether to accept suffix ranges such as `-500`.
+    pub fn allow_suffix(mut self, allow: bool) -> Self {
+        self.allow_suffix = allow;
+        self
+    }
+
+    /// Whether to accept ranges overlapping one another.
+    pub fn allow_overlap(mut self, allow: bool) -> Self {
+        self.allow_overlap = allow;
+        self
+    }
+}
+
+impl Default for StrictOpts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,99 @@
+use crate::parse_utils::is_token;
+
+use std::fmt::{self, Display};
+
+/// A range unit.
+///
+/// Range unit names are case-insensitive, and are normalized to lowercase
+/// when converted from a string.
+///
+/// # Specifications
+///
+/// - [RFC 7233, section 2: Range Units](https://tools.ietf.org/html/rfc7233#section-2)
+///
+/// # Examples
+///
+/// ```
+/// use http_types::range::Unit;
+///
+/// assert_eq!(Unit::other("BYTES"), Unit::Bytes);
+/// assert_eq!(Unit::other("Pages").to_string(), "pages");
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Unit {
+    /// The `bytes` range unit.
+    Bytes,
+    /// A custom range unit.
+    Other(String),
+}
+
+impl Unit {
+    /// Create a range unit from its name.
+    ///
+    /// Registered units such as `bytes` are mapped to their dedicated
+    /// variant, while any other name produces a lowercase [`Unit::Other`].
+    ///
+    /// The name is not validated. [`AcceptRanges`](crate::range::AcceptRanges)
+    /// ignores `none` and names which are not valid tokens, whether they are
+    /// parsed or added.
+    pub fn other(name: impl Into<String>) -> Self {
+        let mut name = name.into();
+        name.make_ascii_lowercase();
+        match name.as_str() {
+            "bytes" => Self::Bytes,
+            _ => Self::Other(name),
+        }
+    }
+
+    /// Returns `true` if the unit name is a valid token, and can be safely
+    /// serialized into a header.
+    pub(crate) fn is_valid(&self) -> bool {
+        match self {
+            Self::Bytes => true,
+            Self::Other(s) => is_token(s),
+        }
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bytes => write!(f, "bytes"),
+            Self::Other(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<&str> for Unit {
+    fn from(s: &str) -> Self {
+        Self::other(s)
+    }
+}
+
+impl From<String> for Unit {
+    fn from(s: String) -> Self {
+        Self::other(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Unit::from("bytes"), Unit::Bytes);
+        assert_eq!(Unit::from("pages"), Unit::Other(String::from("pages")));
+        assert_eq!(Unit::Other(String::from("pages")).to_string(), "pages");
+    }
+
+    #[test]
+    fn normalization() {
+        assert_eq!(Unit::other("Bytes"), Unit::Bytes);
+        assert_eq!(Unit::from("PAGES"), Unit::other("pages"));
+        assert_eq!(Unit::from(String::from("Pages")), Unit::other("pages"));
+        assert_eq!(Unit::other("Pages"), Unit::Other(String::from("pages")));
+    }
+}